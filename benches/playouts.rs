@@ -0,0 +1,35 @@
+//! Random playout throughput, run with `cargo bench --bench playouts [playouts]`
+
+use std::time::Instant;
+
+use uttt::{Game, Rng, WonByPlayer};
+
+fn main() {
+    let playouts: u32 = std::env::args()
+        .skip(1)
+        .find_map(|arg| arg.parse().ok())
+        .unwrap_or(200_000);
+
+    let game = Game::new();
+    let mut rng = Rng::new(0x5EED);
+    let mut results = [0u32; 3];
+    let start = Instant::now();
+    for _ in 0..playouts {
+        match game.random_playout(&mut rng) {
+            WonByPlayer::X => results[0] += 1,
+            WonByPlayer::O => results[1] += 1,
+            _ => results[2] += 1,
+        }
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{} random playouts in {:.2?}: {:.0} playouts/s (X {} / O {} / tie {})",
+        playouts,
+        elapsed,
+        f64::from(playouts) / elapsed.as_secs_f64(),
+        results[0],
+        results[1],
+        results[2]
+    );
+}
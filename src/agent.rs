@@ -0,0 +1,219 @@
+use std::io::{self, BufRead, BufReader, Write};
+
+use crate::limits::SearchLimits;
+use crate::moves::Move;
+use crate::rng::Rng;
+use crate::uttt_game::{Game, Player, WonByPlayer};
+
+/// Anything that can pick moves, humans and bots alike
+pub trait Agent {
+    /// Picks a legal move for the side to move in `game` within `limits`.
+    /// `None` means the agent gave up (e.g. its input was closed)
+    fn choose_move(&mut self, game: &Game, limits: &SearchLimits) -> Option<Move>;
+
+    /// Like [`Agent::choose_move`], also returning how much the agent liked each move
+    /// as probabilities summing to 1. Agents without such a distribution put all of
+    /// it on the move they chose
+    fn choose_move_with_policy(
+        &mut self,
+        game: &Game,
+        limits: &SearchLimits,
+    ) -> Option<(Move, Vec<(Move, f64)>)> {
+        let mv = self.choose_move(game, limits)?;
+        Some((mv, vec![(mv, 1.0)]))
+    }
+
+    /// Short name for logs and results
+    fn name(&self) -> String;
+}
+
+/// Returned by [`play_game`] when an agent doesn't come up with a legal move
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum AgentError {
+    GaveUp { agent_index: usize },
+    IllegalMove { agent_index: usize, attempted: Move },
+}
+
+impl std::fmt::Display for AgentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // agents[0] plays X
+        let letter = |agent_index| if agent_index == 0 { "X" } else { "O" };
+        match *self {
+            Self::GaveUp { agent_index } => write!(f, "{} gave up", letter(agent_index)),
+            Self::IllegalMove {
+                agent_index,
+                attempted,
+            } => write!(
+                f,
+                "{} played the illegal move {}",
+                letter(agent_index),
+                attempted
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AgentError {}
+
+/// Plays `game` to the end, `agents[0]` moves for X and `agents[1]` for O.
+/// `after_move` is called after every move, e.g. to print the board
+pub fn play_game(
+    game: &mut Game,
+    agents: [&mut dyn Agent; 2],
+    limits: &SearchLimits,
+    mut after_move: impl FnMut(&Game, Move),
+) -> Result<WonByPlayer, AgentError> {
+    let [x_agent, o_agent] = agents;
+    while !game.is_finished() {
+        let (agent, agent_index) = match game.get_next_player() {
+            Player::X => (&mut *x_agent, 0),
+            Player::O => (&mut *o_agent, 1),
+        };
+        let mv = agent
+            .choose_move(game, limits)
+            .ok_or(AgentError::GaveUp { agent_index })?;
+        game.make_move(mv).map_err(|_| AgentError::IllegalMove {
+            agent_index,
+            attempted: mv,
+        })?;
+        after_move(game, mv);
+    }
+    Ok(game.result())
+}
+
+/// Plays uniformly random legal moves
+#[derive(Clone, Debug)]
+pub struct RandomAgent {
+    rng: Rng,
+}
+
+impl RandomAgent {
+    pub const fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+        }
+    }
+}
+
+impl Agent for RandomAgent {
+    fn choose_move(&mut self, game: &Game, _limits: &SearchLimits) -> Option<Move> {
+        let moves = game.legal_moves();
+        if moves.len() == 0 {
+            return None;
+        }
+        moves.get(self.rng.below(moves.len() as u32) as usize)
+    }
+
+    fn name(&self) -> String {
+        "random".to_owned()
+    }
+}
+
+/// Asks a human for moves: the big board index (if it can be chosen) and the small board
+/// index, both 1-9, re-prompting until the move is legal
+pub struct HumanAgent<R, W> {
+    input: R,
+    output: W,
+}
+
+impl HumanAgent<BufReader<io::Stdin>, io::Stdout> {
+    /// Human at the terminal. Stdin is only locked while reading and never read past
+    /// the end of a line, so both players can sit at the same terminal
+    pub fn stdio() -> Self {
+        Self::new(BufReader::with_capacity(1, io::stdin()), io::stdout())
+    }
+}
+
+impl<R: BufRead, W: Write> HumanAgent<R, W> {
+    pub const fn new(input: R, output: W) -> Self {
+        Self { input, output }
+    }
+
+    /// Prompts until a number between 1-9 is entered, returns it as an index (0-8).
+    /// `Ok(None)` on invalid input so the caller can restart the turn
+    fn read_index(&mut self, game: &Game, prompt: &str) -> io::Result<Option<u8>> {
+        write!(
+            self.output,
+            "[{}] {}: ",
+            game.get_next_player().get_letter(),
+            prompt
+        )?;
+        self.output.flush()?;
+
+        let mut input = String::new();
+        if self.input.read_line(&mut input)? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let input_trimmed = input.trim();
+
+        if input_trimmed.len() != 1 {
+            writeln!(self.output, "[!] Input one character")?;
+            return Ok(None);
+        }
+
+        let result = match input_trimmed.parse::<u8>() {
+            Ok(val) => val,
+            Err(_) => {
+                writeln!(self.output, "[!] Input a number")?;
+                return Ok(None);
+            }
+        };
+
+        if !(1..=9).contains(&result) {
+            writeln!(self.output, "[!] Input a number between 1-9 (inclusive)")?;
+            return Ok(None);
+        }
+
+        Ok(Some(result - 1))
+    }
+
+    fn prompt_move(&mut self, game: &Game) -> io::Result<Move> {
+        let letter = game.get_next_player().get_letter();
+        loop {
+            let big_board_index = if game.current_move_can_be_put_anywhere() {
+                writeln!(
+                    self.output,
+                    "[{}] You can put your tile on any board",
+                    letter
+                )?;
+                match self.read_index(game, "Big board index")? {
+                    Some(index) => index,
+                    None => continue,
+                }
+            } else {
+                //shouldnt panic as the None variant is ruled out in !game.current_move_can_be_put_anywhere()
+                let index = game.last_sent_board_index().unwrap();
+                writeln!(
+                    self.output,
+                    "[{}] You have to put your tile on board #{}",
+                    letter,
+                    index + 1
+                )?;
+                index
+            };
+
+            let small_board_index = match self.read_index(game, "Small board index")? {
+                Some(index) => index,
+                None => continue,
+            };
+
+            //both indices are 0-8 after read_index
+            let mv = Move::new(big_board_index, small_board_index).unwrap();
+            let mut trial = *game;
+            match trial.make_move(mv) {
+                Ok(_) => return Ok(mv),
+                Err(error) => writeln!(self.output, "[!] {}", error)?,
+            }
+        }
+    }
+}
+
+impl<R: BufRead, W: Write> Agent for HumanAgent<R, W> {
+    fn choose_move(&mut self, game: &Game, _limits: &SearchLimits) -> Option<Move> {
+        self.prompt_move(game).ok()
+    }
+
+    fn name(&self) -> String {
+        "human".to_owned()
+    }
+}
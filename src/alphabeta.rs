@@ -0,0 +1,414 @@
+use std::time::Instant;
+
+use crate::agent::Agent;
+use crate::limits::{SearchLimits, StopFlag};
+use crate::moves::Move;
+use crate::transposition::{Bound, TranspositionTable, TtEntry, TtStats};
+use crate::uttt_game::{has_line, Game, Player, WonByPlayer, LINES};
+
+/// Score of a won game, minus the number of moves it takes to get there
+pub const WIN_SCORE: i32 = 1_000_000;
+const INFINITY: i32 = WIN_SCORE + 1;
+
+/// Whether `score` is a forced win or loss rather than an evaluation
+pub const fn is_win_score(score: i32) -> bool {
+    score.abs() > WIN_SCORE - 100
+}
+
+/// Weights of the evaluation terms, every term is counted for the side to move
+/// and subtracted for the opponent
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct EvalWeights {
+    pub small_board_win: i32,
+    /// Two won small boards on a line whose third board is still open
+    pub big_two_in_a_row: i32,
+    /// Two tiles on a line whose third tile is empty, in an unfinished small board
+    pub small_two_in_a_row: i32,
+    /// Extra for winning the centre small board
+    pub centre_board: i32,
+    /// Centre tile of an unfinished small board
+    pub centre_tile: i32,
+    /// Bonus for the side to move when it was sent to a finished board and can play anywhere
+    pub free_move: i32,
+}
+
+impl Default for EvalWeights {
+    fn default() -> Self {
+        Self {
+            small_board_win: 100,
+            big_two_in_a_row: 200,
+            small_two_in_a_row: 10,
+            centre_board: 50,
+            centre_tile: 5,
+            free_move: 40,
+        }
+    }
+}
+
+/// Lines of `own` with two squares taken and the third not in `blocked`
+fn open_twos(own: u16, blocked: u16) -> i32 {
+    LINES
+        .iter()
+        .filter(|&&line| (own & line).count_ones() == 2 && blocked & line & !own == 0)
+        .count() as i32
+}
+
+/// Static evaluation of an unfinished game from the point of view of the side to move
+pub fn evaluate(game: &Game, weights: &EvalWeights) -> i32 {
+    let big_board = game.get_big_board();
+    let finished = big_board.finished_boards();
+    let player_score = |player: Player| {
+        let won = big_board.won_boards(player);
+        let mut score = weights.small_board_win * won.count_ones() as i32
+            + weights.big_two_in_a_row * open_twos(won, finished);
+        if won & 1 << 4 != 0 {
+            score += weights.centre_board;
+        }
+        for index in (0..9).filter(|index| finished & 1 << index == 0) {
+            let tiles = big_board.get_small_board(index).tiles();
+            let own = tiles.player_mask(player);
+            score +=
+                weights.small_two_in_a_row * open_twos(own, tiles.player_mask(player.opponent()));
+            if own & 1 << 4 != 0 {
+                score += weights.centre_tile;
+            }
+        }
+        score
+    };
+
+    let side = *game.get_next_player();
+    let mut score = player_score(side) - player_score(side.opponent());
+    if game.current_move_can_be_put_anywhere() {
+        score += weights.free_move;
+    }
+    score
+}
+
+/// Outcome of [`AlphaBeta::search`]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SearchResult {
+    /// `None` only if the game is already over
+    pub best_move: Option<Move>,
+    /// From the point of view of the side to move, see [`is_win_score`]
+    pub score: i32,
+    /// Deepest completed iteration, 0 if the search was stopped during the first
+    pub depth: u32,
+    pub nodes: u64,
+    /// Principal variation, starting with `best_move`
+    pub pv: Vec<Move>,
+    /// Transposition table probes during this search
+    pub tt_stats: TtStats,
+}
+
+/// Negamax searcher with alpha-beta pruning and iterative deepening
+#[derive(Clone, Debug)]
+pub struct AlphaBeta {
+    pub weights: EvalWeights,
+    /// Deepest iteration when the limits don't give a depth
+    pub max_depth: u32,
+    nodes: u64,
+    max_nodes: u64,
+    deadline: Option<Instant>,
+    stop: Option<StopFlag>,
+    aborted: bool,
+    hit_horizon: bool,
+    // pv_table[ply] is the best line found from ply on in the current iteration
+    pv_table: Vec<Vec<Move>>,
+    previous_pv: Vec<Move>,
+    //kept between searches, later moves reuse what earlier ones found
+    tt: TranspositionTable,
+}
+
+impl Default for AlphaBeta {
+    fn default() -> Self {
+        Self::new(EvalWeights::default(), 64)
+    }
+}
+
+impl AlphaBeta {
+    pub fn new(weights: EvalWeights, max_depth: u32) -> Self {
+        Self {
+            weights,
+            max_depth,
+            nodes: 0,
+            max_nodes: u64::MAX,
+            deadline: None,
+            stop: None,
+            aborted: false,
+            hit_horizon: false,
+            pv_table: vec![Vec::new(); 82],
+            previous_pv: Vec::new(),
+            tt: TranspositionTable::new(TranspositionTable::DEFAULT_ENTRIES),
+        }
+    }
+
+    /// Replaces the transposition table with an empty one of `entries` slots
+    pub fn with_tt_entries(mut self, entries: usize) -> Self {
+        self.tt = TranspositionTable::new(entries);
+        self
+    }
+
+    pub const fn transposition_table(&self) -> &TranspositionTable {
+        &self.tt
+    }
+
+    /// Searches `game` one ply deeper at a time until `limits` (or `max_depth`) are reached.
+    /// The result is from the deepest completed iteration, or the best root move found
+    /// so far if the first one didn't finish
+    pub fn search(&mut self, game: &Game, limits: &SearchLimits) -> SearchResult {
+        self.search_with_progress(game, limits, |_| ())
+    }
+
+    /// Like [`search`](Self::search), calls `on_iteration` after every completed iteration
+    pub fn search_with_progress(
+        &mut self,
+        game: &Game,
+        limits: &SearchLimits,
+        mut on_iteration: impl FnMut(&SearchResult),
+    ) -> SearchResult {
+        self.nodes = 0;
+        self.max_nodes = limits
+            .nodes
+            .into_iter()
+            .chain(limits.iterations)
+            .min()
+            .unwrap_or(u64::MAX);
+        self.deadline = limits.deadline(Instant::now());
+        self.stop = limits.stop.clone();
+        self.aborted = false;
+        self.previous_pv.clear();
+        self.tt.new_search();
+        self.tt.reset_stats();
+
+        let mut result = SearchResult {
+            best_move: game.legal_moves().next(),
+            score: 0,
+            depth: 0,
+            nodes: 0,
+            pv: Vec::new(),
+            tt_stats: TtStats::default(),
+        };
+        let mut game = *game;
+        let max_depth = limits.depth.unwrap_or(self.max_depth).min(self.max_depth);
+        for depth in 1..=max_depth {
+            self.hit_horizon = false;
+            let score = self.negamax(&mut game, depth, -INFINITY, INFINITY, 0, true);
+            if self.aborted {
+                if result.depth == 0 {
+                    if let Some(&best_move) = self.pv_table[0].first() {
+                        result.best_move = Some(best_move);
+                        result.pv = vec![best_move];
+                    }
+                }
+                break;
+            }
+            self.previous_pv = self.pv_table[0].clone();
+            result = SearchResult {
+                best_move: self.previous_pv.first().copied(),
+                score,
+                depth,
+                nodes: self.nodes,
+                pv: self.previous_pv.clone(),
+                tt_stats: self.tt.stats(),
+            };
+            on_iteration(&result);
+            //nothing left to find once the game is decided or every line reached its end
+            if is_win_score(score) || !self.hit_horizon {
+                break;
+            }
+        }
+        result.nodes = self.nodes;
+        result.tt_stats = self.tt.stats();
+        result
+    }
+
+    /// Whether a limit was hit, the clock and the stop flag are only looked at every so often
+    fn limit_reached(&mut self) -> bool {
+        if self.nodes > self.max_nodes
+            || (self.nodes.is_multiple_of(1024)
+                && (self
+                    .deadline
+                    .is_some_and(|deadline| Instant::now() >= deadline)
+                    || self.stop.as_ref().is_some_and(StopFlag::is_stopped)))
+        {
+            self.aborted = true;
+        }
+        self.aborted
+    }
+
+    fn negamax(
+        &mut self,
+        game: &mut Game,
+        depth: u32,
+        mut alpha: i32,
+        mut beta: i32,
+        ply: usize,
+        on_pv: bool,
+    ) -> i32 {
+        self.nodes += 1;
+        self.pv_table[ply].clear();
+        if self.limit_reached() {
+            return 0;
+        }
+
+        match game.result() {
+            //the player who just moved ended the game
+            WonByPlayer::X | WonByPlayer::O => return -(WIN_SCORE - ply as i32),
+            WonByPlayer::Tie => return 0,
+            WonByPlayer::HasntFinished => (),
+        }
+        if depth == 0 {
+            self.hit_horizon = true;
+            return evaluate(game, &self.weights);
+        }
+
+        let alpha_before = alpha;
+        let entry = self.tt.probe(game.hash());
+        //the root always searches so there is a best move and a full PV to report
+        if let Some(entry) = entry.filter(|entry| ply > 0 && u32::from(entry.depth) >= depth) {
+            let score = score_from_tt(entry.score, ply);
+            match entry.bound {
+                Bound::Exact => (),
+                Bound::Lower => alpha = alpha.max(score),
+                Bound::Upper => beta = beta.min(score),
+            }
+            if entry.bound == Bound::Exact || alpha >= beta {
+                //the entry stands in for a search that may have reached the horizon
+                self.hit_horizon = true;
+                let line = self.tt_line(game, depth);
+                self.pv_table[ply].extend(line);
+                return score;
+            }
+        }
+
+        let pv_move = if on_pv {
+            self.previous_pv.get(ply).copied()
+        } else {
+            None
+        };
+        let mut moves = [Move::from_index_unchecked(0); 81];
+        let count = order_moves(
+            game,
+            pv_move.or(entry.and_then(|entry| entry.best_move)),
+            &mut moves,
+        );
+
+        let mut best = -INFINITY;
+        for &mv in &moves[..count] {
+            game.make_move(mv).unwrap();
+            let score = -self.negamax(game, depth - 1, -beta, -alpha, ply + 1, pv_move == Some(mv));
+            game.unmake_move();
+            if self.aborted {
+                return 0;
+            }
+
+            if score > best {
+                best = score;
+                let (current, deeper) = self.pv_table.split_at_mut(ply + 1);
+                current[ply].clear();
+                current[ply].push(mv);
+                current[ply].extend_from_slice(&deeper[0]);
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = if best <= alpha_before {
+            Bound::Upper
+        } else if best >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.tt.store(TtEntry {
+            key: game.hash(),
+            depth: depth as u8,
+            bound,
+            score: score_to_tt(best, ply),
+            best_move: self.pv_table[ply].first().copied(),
+        });
+        best
+    }
+
+    /// Follows the stored best moves from `game` for at most `depth` moves
+    fn tt_line(&self, game: &Game, depth: u32) -> Vec<Move> {
+        let mut game = *game;
+        let mut line = Vec::new();
+        while line.len() < depth as usize {
+            match self.tt.get(game.hash()).and_then(|entry| entry.best_move) {
+                Some(mv) if game.make_move(mv).is_ok() => line.push(mv),
+                _ => break,
+            }
+        }
+        line
+    }
+}
+
+/// Writes the legal moves into `moves` best first and returns how many there are:
+/// the previous iteration's move, then small board wins, blocks, centre tiles, with
+/// moves that let the opponent play anywhere last
+fn order_moves(game: &Game, pv_move: Option<Move>, moves: &mut [Move; 81]) -> usize {
+    let big_board = game.get_big_board();
+    let side = *game.get_next_player();
+    let mut scored = [(0i32, Move::from_index_unchecked(0)); 81];
+    let mut count = 0;
+    for mv in game.legal_moves() {
+        let tiles = big_board.get_small_board(mv.small_board_pos()).tiles();
+        let bit = 1 << mv.tile_pos();
+        let mut score = 0;
+        if Some(mv) == pv_move {
+            score += 1_000_000;
+        }
+        let wins_board = has_line(tiles.player_mask(side) | bit);
+        if wins_board {
+            score += 1000;
+        }
+        if has_line(tiles.player_mask(side.opponent()) | bit) {
+            score += 500;
+        }
+        if mv.tile_pos() == 4 {
+            score += 10;
+        }
+        let sends_to_finished = big_board.finished_boards() & 1 << mv.tile_pos() != 0
+            || (wins_board && mv.tile_pos() == mv.small_board_pos());
+        if sends_to_finished {
+            score -= 300;
+        }
+        scored[count] = (score, mv);
+        count += 1;
+    }
+    scored[..count].sort_unstable_by_key(|&(score, _)| std::cmp::Reverse(score));
+    for (slot, &(_, mv)) in moves.iter_mut().zip(&scored[..count]) {
+        *slot = mv;
+    }
+    count
+}
+
+impl Agent for AlphaBeta {
+    fn choose_move(&mut self, game: &Game, limits: &SearchLimits) -> Option<Move> {
+        self.search(game, limits).best_move
+    }
+
+    fn name(&self) -> String {
+        "alphabeta".to_owned()
+    }
+}
+
+/// Win scores count moves from the root, in the table they count from the stored position
+fn score_to_tt(score: i32, ply: usize) -> i32 {
+    if is_win_score(score) {
+        score + score.signum() * ply as i32
+    } else {
+        score
+    }
+}
+
+fn score_from_tt(score: i32, ply: usize) -> i32 {
+    if is_win_score(score) {
+        score - score.signum() * ply as i32
+    } else {
+        score
+    }
+}
@@ -0,0 +1,164 @@
+use std::fmt::Display;
+use std::io::{self, BufRead, Write};
+
+use crate::agent::Agent;
+use crate::limits::SearchLimits;
+use crate::moves::Move;
+use crate::uttt_game::{BoardError, Game};
+
+/// Returned by [`play_codingame`]
+#[derive(Debug)]
+pub enum CodinGameError {
+    Io(io::Error),
+    /// A line that isn't `row col` or a number where one was expected
+    InvalidLine {
+        line: String,
+    },
+    IllegalOpponentMove {
+        row: u8,
+        col: u8,
+        error: BoardError,
+    },
+    /// The referee's valid actions aren't the legal moves of the game as we know it
+    ActionsMismatch {
+        expected: Vec<Move>,
+        actual: Vec<Move>,
+    },
+    AgentGaveUp,
+    IllegalAgentMove {
+        attempted: Move,
+        error: BoardError,
+    },
+}
+
+impl Display for CodinGameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{}", error),
+            Self::InvalidLine { line } => write!(f, "can't read \"{}\"", line),
+            Self::IllegalOpponentMove { row, col, error } => {
+                write!(f, "opponent move {} {}: {}", row, col, error)
+            }
+            Self::ActionsMismatch { expected, actual } => write!(
+                f,
+                "expected {} valid actions but got {}",
+                expected.len(),
+                actual.len()
+            ),
+            Self::AgentGaveUp => write!(f, "the agent gave up"),
+            Self::IllegalAgentMove { attempted, error } => {
+                write!(f, "the agent played {}: {}", attempted, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CodinGameError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::IllegalOpponentMove { error, .. } | Self::IllegalAgentMove { error, .. } => {
+                Some(error)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CodinGameError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// Plays one game in the CodinGame referee format: every turn reads the opponent's
+/// move as `row col` on the 9x9 grid (`-1 -1` if we move first), the number of valid
+/// actions and one `row col` line per action, then answers with our `row col`.
+/// Returns once the input ends
+pub fn play_codingame(
+    mut input: impl BufRead,
+    mut output: impl Write,
+    agent: &mut dyn Agent,
+    limits: &SearchLimits,
+) -> Result<(), CodinGameError> {
+    let mut game = Game::new();
+    let mut line = String::new();
+    loop {
+        let Some(opponent_move) = read_line(&mut input, &mut line)? else {
+            return Ok(());
+        };
+        match parse_pair(opponent_move)? {
+            (-1, -1) => (),
+            (row, col) => {
+                let (row, col) = (to_index(row, opponent_move)?, to_index(col, opponent_move)?);
+                let mv = Move::from_row_col(row, col)
+                    .map_err(|error| CodinGameError::IllegalOpponentMove { row, col, error })?;
+                game.make_move(mv)
+                    .map_err(|error| CodinGameError::IllegalOpponentMove { row, col, error })?;
+            }
+        }
+
+        let action_count = read_line(&mut input, &mut line)?.unwrap_or_default();
+        let action_count: usize = action_count
+            .trim()
+            .parse()
+            .map_err(|_| invalid_line(action_count))?;
+        let mut actions = Vec::with_capacity(action_count);
+        for _ in 0..action_count {
+            let action = read_line(&mut input, &mut line)?.unwrap_or_default();
+            let (row, col) = parse_pair(action)?;
+            let mv = Move::from_row_col(to_index(row, action)?, to_index(col, action)?)
+                .map_err(|_| invalid_line(action))?;
+            actions.push(mv);
+        }
+        actions.sort_unstable_by_key(|mv| mv.index());
+        let expected: Vec<Move> = game.legal_moves().collect();
+        if actions != expected {
+            return Err(CodinGameError::ActionsMismatch {
+                expected,
+                actual: actions,
+            });
+        }
+
+        let mv = agent
+            .choose_move(&game, limits)
+            .ok_or(CodinGameError::AgentGaveUp)?;
+        game.make_move(mv)
+            .map_err(|error| CodinGameError::IllegalAgentMove {
+                attempted: mv,
+                error,
+            })?;
+        writeln!(output, "{} {}", mv.row(), mv.col())?;
+        output.flush()?;
+    }
+}
+
+/// Next line without its line break, `None` at the end of input
+fn read_line<'a>(
+    input: &mut impl BufRead,
+    line: &'a mut String,
+) -> Result<Option<&'a str>, CodinGameError> {
+    line.clear();
+    if input.read_line(line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line.trim_end()))
+}
+
+fn invalid_line(line: &str) -> CodinGameError {
+    CodinGameError::InvalidLine {
+        line: line.to_owned(),
+    }
+}
+
+fn parse_pair(line: &str) -> Result<(i8, i8), CodinGameError> {
+    let mut numbers = line.split_whitespace().map(str::parse::<i8>);
+    match (numbers.next(), numbers.next(), numbers.next()) {
+        (Some(Ok(row)), Some(Ok(col)), None) => Ok((row, col)),
+        _ => Err(invalid_line(line)),
+    }
+}
+
+fn to_index(number: i8, line: &str) -> Result<u8, CodinGameError> {
+    u8::try_from(number).map_err(|_| invalid_line(line))
+}
@@ -0,0 +1,333 @@
+use std::io::{self, BufRead, Write};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::alphabeta::{is_win_score, AlphaBeta, SearchResult, WIN_SCORE};
+use crate::limits::{SearchLimits, StopFlag};
+use crate::mcts::{Mcts, MctsResult, Parallelism};
+use crate::moves::{Move, ParseMoveError};
+use crate::uttt_game::{Game, Player};
+
+/// Highest `Threads` option value
+const MAX_THREADS: usize = 256;
+
+/// The searcher behind the `Engine` option
+#[derive(Clone, Debug)]
+enum Searcher {
+    AlphaBeta(AlphaBeta),
+    Mcts(Mcts),
+}
+
+struct RunningSearch {
+    handle: JoinHandle<Searcher>,
+    stop: StopFlag,
+    /// Started by `go` without limits, it only ends on `stop`
+    unlimited: bool,
+}
+
+/// Engine side of a line based protocol modelled on UCI, so the engine can run under
+/// GUIs and tournament managers without linking to the crate. Feed it lines with
+/// [`Engine::handle_line`] or let [`Engine::run`] read them
+///
+/// Commands, one per line:
+/// - `uti`: identify, answered by `id name ...`, `id author ...`, the options and `utiok`
+/// - `isready`: answered by `readyok`
+/// - `setoption name <Engine|Threads> value <v>`: `Engine` is `alphabeta` or `mcts`,
+///   `Threads` is the number of MCTS threads, 1 to 256
+/// - `newgame`: back to the initial position, dropping what the searchers learned
+/// - `position startpos [moves <m>...]` or `position pos <tiles> <side> <sent> [moves <m>...]`,
+///   moves in [`Move`] notation and the position in [`Game::position`] format
+/// - `go [movetime <ms>] [depth <n>] [nodes <n>] [iterations <n>] [xtime <ms>] [otime <ms>]
+///   [xinc <ms>] [oinc <ms>] [infinite]`: starts searching in the background, without
+///   limits until `stop`
+/// - `stop`: ends the search, which answers with its `bestmove`
+/// - `quit`
+///
+/// A search reports `info depth <d> score <cp <n>|win <plies>|loss <plies>> nodes <n>
+/// time <ms> [tthits <permille>] pv <m>...` lines and ends with `bestmove <m>`
+/// (`bestmove none` if the game is over). Anything that can't be understood is answered
+/// with `info string <reason>` and otherwise ignored
+pub struct Engine<W: Write + Send + 'static> {
+    game: Game,
+    threads: usize,
+    //taken by the search thread while it runs
+    searcher: Option<Searcher>,
+    running: Option<RunningSearch>,
+    output: Arc<Mutex<W>>,
+}
+
+impl<W: Write + Send + 'static> Engine<W> {
+    pub fn new(output: W) -> Self {
+        Self {
+            game: Game::new(),
+            threads: 1,
+            searcher: Some(Searcher::AlphaBeta(AlphaBeta::default())),
+            running: None,
+            output: Arc::new(Mutex::new(output)),
+        }
+    }
+
+    /// Handles every line of `input` until `quit` or the end of input and gives the
+    /// output back. `quit` stops the running search, at the end of input it may finish
+    /// unless it has no limits
+    pub fn run(input: impl BufRead, output: W) -> io::Result<W> {
+        let mut engine = Self::new(output);
+        for line in input.lines() {
+            if !engine.handle_line(&line?)? {
+                engine.stop_search();
+                break;
+            }
+        }
+        if engine
+            .running
+            .as_ref()
+            .is_some_and(|running| running.unlimited)
+        {
+            engine.stop_search();
+        }
+        Ok(engine.into_output())
+    }
+
+    /// Handles one command, `false` after `quit`
+    pub fn handle_line(&mut self, line: &str) -> io::Result<bool> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, args)) = words.split_first() else {
+            return Ok(true);
+        };
+        match command {
+            "uti" => self.send(&format!(
+                "id name uttt {}\nid author the uttt developers\n\
+                 option name Engine type combo default alphabeta var alphabeta var mcts\n\
+                 option name Threads type spin default 1 min 1 max {}\nutiok",
+                env!("CARGO_PKG_VERSION"),
+                MAX_THREADS
+            ))?,
+            "isready" => self.send("readyok")?,
+            "setoption" => {
+                self.stop_search();
+                self.set_option(args)?;
+            }
+            "newgame" => {
+                self.stop_search();
+                self.game = Game::new();
+                self.searcher = Some(match self.searcher.take() {
+                    Some(Searcher::Mcts(_)) => Searcher::Mcts(self.new_mcts()),
+                    _ => Searcher::AlphaBeta(AlphaBeta::default()),
+                });
+            }
+            "position" => {
+                self.stop_search();
+                match parse_position(args) {
+                    Ok(game) => self.game = game,
+                    Err(error) => self.send(&format!("info string {}", error))?,
+                }
+            }
+            "go" => {
+                self.stop_search();
+                match parse_limits(args, *self.game.get_next_player()) {
+                    Ok(limits) => self.start_search(limits),
+                    Err(error) => self.send(&format!("info string {}", error))?,
+                }
+            }
+            "stop" => self.stop_search(),
+            "quit" => return Ok(false),
+            _ => self.send(&format!("info string unknown command {}", command))?,
+        }
+        Ok(true)
+    }
+
+    /// Stops the running search, if any, and waits for its `bestmove`
+    pub fn stop_search(&mut self) {
+        if let Some(running) = &self.running {
+            running.stop.stop();
+        }
+        self.wait_for_search();
+    }
+
+    /// Lets the running search, if any, finish within its limits
+    pub fn wait_for_search(&mut self) {
+        if let Some(running) = self.running.take() {
+            self.searcher = Some(running.handle.join().expect("search thread panicked"));
+        }
+    }
+
+    /// Waits for the running search to finish and gives the output back
+    pub fn into_output(mut self) -> W {
+        self.wait_for_search();
+        let output = Arc::try_unwrap(self.output)
+            .ok()
+            .expect("the search thread is done");
+        output
+            .into_inner()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn send(&self, text: &str) -> io::Result<()> {
+        send(&self.output, text)
+    }
+
+    fn new_mcts(&self) -> Mcts {
+        Mcts::new(Mcts::DEFAULT_EXPLORATION, u64::MAX, 0)
+            .with_threads(self.threads, Parallelism::Tree)
+    }
+
+    fn set_option(&mut self, args: &[&str]) -> io::Result<()> {
+        let (name, value) = match args {
+            ["name", name, "value", value] => (*name, *value),
+            _ => return self.send("info string expected setoption name <name> value <value>"),
+        };
+        match (name, value) {
+            ("Engine", "alphabeta") => {
+                self.searcher = Some(Searcher::AlphaBeta(AlphaBeta::default()))
+            }
+            ("Engine", "mcts") => self.searcher = Some(Searcher::Mcts(self.new_mcts())),
+            ("Threads", threads) => match threads.parse() {
+                Ok(threads) if (1..=MAX_THREADS).contains(&threads) => {
+                    self.threads = threads;
+                    if let Some(Searcher::Mcts(mcts)) = &mut self.searcher {
+                        mcts.threads = threads;
+                    }
+                }
+                _ => return self.send(&format!("info string invalid thread count {}", threads)),
+            },
+            _ => return self.send(&format!("info string unknown option {} {}", name, value)),
+        }
+        Ok(())
+    }
+
+    fn start_search(&mut self, limits: SearchLimits) {
+        let unlimited = limits.is_unlimited();
+        let stop = StopFlag::new();
+        let limits = limits.with_stop(stop.clone());
+        let mut searcher = self.searcher.take().expect("no search is running");
+        let game = self.game;
+        let output = Arc::clone(&self.output);
+        let handle = thread::spawn(move || {
+            let start = Instant::now();
+            //a closed output can't be reported anywhere, the search just ends
+            let best_move = match &mut searcher {
+                Searcher::AlphaBeta(alpha_beta) => {
+                    alpha_beta
+                        .search_with_progress(&game, &limits, |result| {
+                            let _ = send(&output, &alpha_beta_info(result, start.elapsed()));
+                        })
+                        .best_move
+                }
+                Searcher::Mcts(mcts) => {
+                    let result = mcts.search(&game, &limits);
+                    let _ = send(&output, &mcts_info(&result, start.elapsed()));
+                    result.best_move
+                }
+            };
+            let best_move = best_move.map_or("none".to_owned(), |mv| mv.to_string());
+            let _ = send(&output, &format!("bestmove {}", best_move));
+            searcher
+        });
+        self.running = Some(RunningSearch {
+            handle,
+            stop,
+            unlimited,
+        });
+    }
+}
+
+/// Writes `text` and a newline and flushes, so the other side sees it right away
+fn send(output: &Mutex<impl Write>, text: &str) -> io::Result<()> {
+    let mut output = output
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    writeln!(output, "{}", text)?;
+    output.flush()
+}
+
+fn parse_position(args: &[&str]) -> Result<Game, String> {
+    let (mut game, moves) = match args {
+        ["startpos", moves @ ..] => (Game::new(), moves),
+        ["pos", tiles, side, sent_board, moves @ ..] => (
+            Game::from_position(&format!("{} {} {}", tiles, side, sent_board))
+                .map_err(|error| error.to_string())?,
+            moves,
+        ),
+        _ => return Err("expected position startpos or position pos <position>".to_owned()),
+    };
+    let moves = match moves {
+        [] => &[][..],
+        ["moves", moves @ ..] => moves,
+        _ => return Err(format!("expected moves instead of {}", moves[0])),
+    };
+    for mv in moves {
+        let mv: Move = mv
+            .parse()
+            .map_err(|error: ParseMoveError| error.to_string())?;
+        game.make_move(mv)
+            .map_err(|error| format!("{}: {}", mv, error))?;
+    }
+    Ok(game)
+}
+
+fn parse_limits(args: &[&str], side: Player) -> Result<SearchLimits, String> {
+    let mut limits = SearchLimits::default();
+    let mut args = args.iter();
+    while let Some(&name) = args.next() {
+        if name == "infinite" {
+            continue;
+        }
+        let value = args.next().ok_or(format!("missing value for {}", name))?;
+        let number: u64 = value
+            .parse()
+            .map_err(|_| format!("invalid value {} for {}", value, name))?;
+        let millis = Duration::from_millis(number);
+        match (name, side) {
+            ("movetime", _) => limits.move_time = Some(millis),
+            ("depth", _) => limits.depth = Some(number as u32),
+            ("nodes", _) => limits.nodes = Some(number),
+            ("iterations", _) => limits.iterations = Some(number),
+            ("xtime", Player::X) | ("otime", Player::O) => limits.remaining = Some(millis),
+            ("xinc", Player::X) | ("oinc", Player::O) => limits.increment = millis,
+            ("xtime" | "otime" | "xinc" | "oinc", _) => (),
+            _ => return Err(format!("unknown go parameter {}", name)),
+        }
+    }
+    Ok(limits)
+}
+
+fn alpha_beta_info(result: &SearchResult, elapsed: Duration) -> String {
+    let score = if is_win_score(result.score) {
+        let plies = WIN_SCORE - result.score.abs();
+        if result.score > 0 {
+            format!("win {}", plies)
+        } else {
+            format!("loss {}", plies)
+        }
+    } else {
+        format!("cp {}", result.score)
+    };
+    let mut info = format!(
+        "info depth {} score {} nodes {} time {} tthits {}",
+        result.depth,
+        score,
+        result.nodes,
+        elapsed.as_millis(),
+        (result.tt_stats.hit_rate() * 1000.0).round()
+    );
+    if !result.pv.is_empty() {
+        let pv: Vec<String> = result.pv.iter().map(Move::to_string).collect();
+        info += &format!(" pv {}", pv.join(" "));
+    }
+    info
+}
+
+/// MCTS has no depth, its value (0 to 1) is reported as -1000 to 1000 centipoints
+fn mcts_info(result: &MctsResult, elapsed: Duration) -> String {
+    let mut info = format!(
+        "info depth 1 score cp {} nodes {} time {}",
+        ((result.value - 0.5) * 2000.0).round(),
+        result.iterations,
+        elapsed.as_millis()
+    );
+    if let Some(best_move) = result.best_move {
+        info += &format!(" pv {}", best_move);
+    }
+    info
+}
@@ -0,0 +1,126 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use crate::agent::Agent;
+use crate::limits::SearchLimits;
+use crate::moves::Move;
+use crate::uttt_game::{Game, Player};
+
+/// Engine running in another process and speaking the [`Engine`](crate::Engine)
+/// protocol on its stdin/stdout, e.g. an older build of this crate
+pub struct ExternalEngine {
+    child: Child,
+    input: ChildStdin,
+    output: BufReader<ChildStdout>,
+    name: String,
+}
+
+impl ExternalEngine {
+    /// Starts `program` with `args` and waits until it has identified itself
+    pub fn spawn(program: &str, args: &[&str]) -> io::Result<Self> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let input = child.stdin.take().expect("stdin is piped");
+        let output = BufReader::new(child.stdout.take().expect("stdout is piped"));
+        let mut engine = Self {
+            child,
+            input,
+            output,
+            name: program.to_owned(),
+        };
+
+        engine.send("uti")?;
+        loop {
+            let line = engine.read_line()?;
+            if let Some(name) = line.strip_prefix("id name ") {
+                engine.name = name.to_owned();
+            } else if line == "utiok" {
+                return Ok(engine);
+            }
+        }
+    }
+
+    fn send(&mut self, line: &str) -> io::Result<()> {
+        writeln!(self.input, "{}", line)?;
+        self.input.flush()
+    }
+
+    /// Next line without its line break, an error once the engine closed its output
+    fn read_line(&mut self) -> io::Result<String> {
+        let mut line = String::new();
+        if self.output.read_line(&mut line)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("{} closed its output", self.name),
+            ));
+        }
+        Ok(line.trim_end().to_owned())
+    }
+
+    fn best_move(&mut self, game: &Game, limits: &SearchLimits) -> io::Result<Option<Move>> {
+        self.send(&format!("position pos {}", game.position()))?;
+        self.send(&go_command(limits, *game.get_next_player()))?;
+        loop {
+            let line = self.read_line()?;
+            if let Some(best_move) = line.strip_prefix("bestmove ") {
+                return Ok(best_move.parse().ok());
+            }
+        }
+    }
+}
+
+/// `go` with the limits that apply, `infinite` if there are none
+fn go_command(limits: &SearchLimits, side: Player) -> String {
+    let mut command = "go".to_owned();
+    if let Some(move_time) = limits.move_time {
+        command += &format!(" movetime {}", move_time.as_millis());
+    }
+    if let Some(depth) = limits.depth {
+        command += &format!(" depth {}", depth);
+    }
+    if let Some(nodes) = limits.nodes {
+        command += &format!(" nodes {}", nodes);
+    }
+    if let Some(iterations) = limits.iterations {
+        command += &format!(" iterations {}", iterations);
+    }
+    if let Some(remaining) = limits.remaining {
+        let letter = side.get_letter().to_ascii_lowercase();
+        command += &format!(
+            " {}time {} {}inc {}",
+            letter,
+            remaining.as_millis(),
+            letter,
+            limits.increment.as_millis()
+        );
+    }
+    if limits.is_unlimited() {
+        command += " infinite";
+    }
+    command
+}
+
+impl Agent for ExternalEngine {
+    /// Gives up if the engine can't be talked to or doesn't come up with a move.
+    /// Without limits the engine searches until it is stopped, so this never returns
+    fn choose_move(&mut self, game: &Game, limits: &SearchLimits) -> Option<Move> {
+        self.best_move(game, limits).ok().flatten()
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+impl Drop for ExternalEngine {
+    fn drop(&mut self) {
+        //the engine may be gone already, then there is nothing left to clean up
+        if self.send("quit").is_err() {
+            let _ = self.child.kill();
+        }
+        let _ = self.child.wait();
+    }
+}
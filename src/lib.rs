@@ -0,0 +1,53 @@
+//! Ultimate Tic Tac Toe game logic.
+//!
+//! [`Game`] is the entry point: it tracks the nine small boards, whose turn it is
+//! and which board the next move has to be played on.
+//!
+//! ```
+//! use uttt::{Game, Move, WonByPlayer};
+//!
+//! let mut game = Game::new();
+//! let status = game.make_move(Move::new(4, 4).unwrap()).unwrap();
+//! assert!(matches!(status, WonByPlayer::HasntFinished));
+//! assert_eq!(game.last_sent_board_index(), Some(4));
+//! ```
+
+mod agent;
+mod alphabeta;
+mod codingame;
+mod engine;
+mod external;
+mod limits;
+mod mcts;
+mod moves;
+mod perft;
+mod position;
+mod record;
+mod rng;
+mod selfplay;
+mod tournament;
+mod transposition;
+mod uttt_game;
+mod zobrist;
+
+pub use agent::{play_game, Agent, AgentError, HumanAgent, RandomAgent};
+pub use alphabeta::{evaluate, is_win_score, AlphaBeta, EvalWeights, SearchResult, WIN_SCORE};
+pub use codingame::{play_codingame, CodinGameError};
+pub use engine::Engine;
+pub use external::ExternalEngine;
+pub use limits::{SearchLimits, StopFlag};
+pub use mcts::{Mcts, MctsResult, Parallelism};
+pub use moves::{LegalMoves, Move, ParseMoveError};
+pub use position::PositionError;
+pub use record::RecordError;
+pub use rng::Rng;
+pub use selfplay::{
+    encode_state, generate_shards, play_self_play_game, shard_file_name, Sample, SelfPlayConfig,
+    SelfPlayError, Shard, STATE_SIZE,
+};
+pub use tournament::{
+    elo_from_fraction, fraction_from_elo, run_tournament, GameOutcome, Score, Sprt, SprtStatus,
+    TournamentConfig,
+};
+pub use transposition::{Bound, TranspositionTable, TtEntry, TtStats};
+pub use uttt_game::{BigBoard, BoardError, Game, Mark, NineTiles, Player, SmallBoard, WonByPlayer};
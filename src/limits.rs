@@ -0,0 +1,123 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Shared flag for stopping a running search from another thread, clones refer
+/// to the same flag
+#[derive(Clone, Default, Debug)]
+pub struct StopFlag(Arc<AtomicBool>);
+
+impl StopFlag {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks every search holding this flag to return its best move so far
+    pub fn stop(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Lowers the flag so it can be used for the next search
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+}
+
+/// When a search has to return. Every limit that is set applies, whichever is
+/// reached first ends the search; with none set the searcher's own caps apply
+#[derive(Clone, Default, Debug)]
+pub struct SearchLimits {
+    /// Fixed thinking time for this move
+    pub move_time: Option<Duration>,
+    /// Alpha-beta iterative deepening depth, for MCTS how deep its tree may grow
+    pub depth: Option<u32>,
+    /// Alpha-beta nodes, MCTS adds one node per playout so it counts them as playouts
+    pub nodes: Option<u64>,
+    /// MCTS playouts, alpha-beta counts them as nodes
+    pub iterations: Option<u64>,
+    /// Time left on the side to move's clock, see [`SearchLimits::time_budget`]
+    pub remaining: Option<Duration>,
+    /// Time added to the clock after each move
+    pub increment: Duration,
+    pub stop: Option<StopFlag>,
+}
+
+impl SearchLimits {
+    /// Moves the side to move is assumed to still have to make when spreading its clock
+    const MOVES_TO_GO: u32 = 20;
+
+    pub fn move_time(move_time: Duration) -> Self {
+        Self {
+            move_time: Some(move_time),
+            ..Self::default()
+        }
+    }
+
+    pub fn depth(depth: u32) -> Self {
+        Self {
+            depth: Some(depth),
+            ..Self::default()
+        }
+    }
+
+    pub fn nodes(nodes: u64) -> Self {
+        Self {
+            nodes: Some(nodes),
+            ..Self::default()
+        }
+    }
+
+    pub fn iterations(iterations: u64) -> Self {
+        Self {
+            iterations: Some(iterations),
+            ..Self::default()
+        }
+    }
+
+    pub fn clock(remaining: Duration, increment: Duration) -> Self {
+        Self {
+            remaining: Some(remaining),
+            increment,
+            ..Self::default()
+        }
+    }
+
+    /// Same limits, stoppable through `stop`
+    pub fn with_stop(mut self, stop: StopFlag) -> Self {
+        self.stop = Some(stop);
+        self
+    }
+
+    /// Time this move may take: `move_time`, or a share of the clock plus most of the
+    /// increment, never more than half of what is left
+    pub fn time_budget(&self) -> Option<Duration> {
+        let from_clock = self.remaining.map(|remaining| {
+            (remaining / Self::MOVES_TO_GO + self.increment * 3 / 4).min(remaining / 2)
+        });
+        match (self.move_time, from_clock) {
+            (Some(move_time), Some(from_clock)) => Some(move_time.min(from_clock)),
+            (move_time, from_clock) => move_time.or(from_clock),
+        }
+    }
+
+    /// Point in time the search has to be done by, if it started at `start`
+    pub fn deadline(&self, start: Instant) -> Option<Instant> {
+        self.time_budget().map(|budget| start + budget)
+    }
+
+    /// Whether only the stop flag (or the searcher's own caps) can end the search
+    pub fn is_unlimited(&self) -> bool {
+        self.time_budget().is_none()
+            && self.depth.is_none()
+            && self.nodes.is_none()
+            && self.iterations.is_none()
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stop.as_ref().is_some_and(StopFlag::is_stopped)
+    }
+}
@@ -1,124 +1,353 @@
-use std::io::{self, Write};
-use uttt_game::BoardError;
+use std::env;
+use std::io;
+use std::path::PathBuf;
+use std::process;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-mod uttt_game;
+use uttt::{
+    generate_shards, play_codingame, play_game, run_tournament, Agent, AlphaBeta, Engine,
+    ExternalEngine, Game, HumanAgent, Mcts, Parallelism, RandomAgent, Sample, SearchLimits,
+    SelfPlayConfig, Sprt, SprtStatus, TournamentConfig, WonByPlayer,
+};
 
-/* This code needs to be refactored hard, maybe move main loop into another file or perhaps in uttt_game.rs?
- * Actually, I might need to redo all of this project because its way too mangled up
- *
- * */
-fn main() {
-    let mut game = uttt_game::Game::new();
-    //game.make_move(0, 1, uttt_game::Player::X);
-    game.print_game();
-    loop {
-        let mut input = String::new();
-        let big_board_index = if game.current_move_can_be_put_anywhere() {
-            println!(
-                "[{}] You can put your tile on any board",
-                game.get_next_player().get_letter()
-            );
-            print!(
-                "[{}] Big board index: ",
-                game.get_next_player().get_letter()
-            );
-            io::stdout().flush().unwrap();
+const USAGE: &str = "usage: uttt [play] [X_AGENT] [O_AGENT]
+       uttt engine
+       uttt codingame [AGENT]
+       uttt tournament [--games N] [--movetime MS] [--openings PLIES] [--seed N]
+                       [--sprt ELO0 ELO1] AGENT_A AGENT_B
+       uttt selfplay [--shards N] [--games N] [--iterations N] [--depth N] [--movetime MS]
+                     [--sample-plies N] [--seed N] [--threads N] [--out DIR] [AGENT]
 
-            io::stdin()
-                .read_line(&mut input)
-                .expect("[!!!] Unexpected crash happened");
-            let input_trimmed = input.trim();
+engine speaks the text protocol described at uttt::Engine on stdin/stdout,
+codingame plays one game in the CodinGame referee format with AGENT (default mcts),
+tournament plays N games (default 100) of 100ms per move after 4 random moves,
+AGENT_A and AGENT_B take turns to start,
+selfplay writes N shards (default 1) of N games (default 100) of AGENT (default mcts)
+against itself to DIR (default selfplay), searching 800 MCTS iterations or alpha-beta
+depth 4 per move and drawing the first 10 moves from the policy
 
-            if input_trimmed.len() != 1 {
-                println!("[!] Input one character");
-                continue;
-            }
+agents: human (default), random, alphabeta, mcts, and in tournaments
+        cmd:\"PROGRAM ARGS\" for an external engine speaking the engine protocol";
 
-            let result = match input_trimmed.parse::<u8>() {
-                Ok(val) => val,
-                Err(_) => {
-                    println!("[!] Input a number");
-                    continue;
-                }
-            };
+/// Thinking time given to the engine agents for each move
+const MOVE_TIME: Duration = Duration::from_secs(1);
 
-            if !(1 <= result && result <= 9) {
-                println!("[!] Input a number between 1-9 (inclusive)");
-                continue;
-            }
+/// CodinGame allows 100ms per turn, this leaves some for reading and writing
+const CODINGAME_MOVE_TIME: Duration = Duration::from_millis(85);
 
-            result - 1
-        } else {
-            println!(
-                "[{}] You have to put your tile on board #{}",
-                game.get_next_player().get_letter(),
-                game.last_sent_board_index().unwrap() + 1
-            ); //shouldnt panic as the None variant is ruled out in !game.current_move_can_be_put_anywhere()
-            game.last_sent_board_index().unwrap()
-        };
+/// Seed for the randomised agents, different on every run
+fn time_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_nanos() as u64)
+}
 
-        print!(
-            "[{}] Small board index: ",
-            game.get_next_player().get_letter()
-        );
-        io::stdout().flush().unwrap();
+/// Builds the agent called `name` on the command line
+fn agent_from_name(name: &str) -> Option<Box<dyn Agent>> {
+    match name {
+        "human" => Some(Box::new(HumanAgent::stdio())),
+        "random" => Some(Box::new(RandomAgent::new(time_seed()))),
+        "alphabeta" => Some(Box::new(AlphaBeta::default())),
+        "mcts" => {
+            let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+            Some(Box::new(
+                Mcts::new(Mcts::DEFAULT_EXPLORATION, u64::MAX, time_seed())
+                    .with_threads(threads, Parallelism::Tree),
+            ))
+        }
+        _ => None,
+    }
+}
+
+fn play(args: &[String]) {
+    if args.len() > 2 {
+        eprintln!("{}", USAGE);
+        process::exit(2);
+    }
+    let mut agents = [0, 1].map(|i| {
+        let name = args.get(i).map_or("human", String::as_str);
+        agent_from_name(name).unwrap_or_else(|| {
+            eprintln!("[!] Unknown agent \"{}\"\n{}", name, USAGE);
+            process::exit(2);
+        })
+    });
 
-        input.clear();
-        io::stdin()
-            .read_line(&mut input)
-            .expect("[!!!] Unexpected crash happened");
-        let input_trimmed = input.trim();
+    let mut game = Game::new();
+    game.print_game();
+    let [x_agent, o_agent] = &mut agents;
+    let result = play_game(
+        &mut game,
+        [x_agent.as_mut(), o_agent.as_mut()],
+        &SearchLimits::move_time(MOVE_TIME),
+        |game, _| {
+            print!("\x1B[2J\x1B[1;1H"); //clear screen
+            game.print_game();
+        },
+    );
 
-        if input_trimmed.len() != 1 {
-            println!("[!] Input one character");
-            continue;
-        }
+    match result {
+        Ok(WonByPlayer::X) => println!("[#] Game won by X"),
+        Ok(WonByPlayer::O) => println!("[#] Game won by O"),
+        Ok(_) => println!("[#] Game tied"),
+        Err(error) => println!("[!] Game aborted, {}", error),
+    }
+    println!("[#] {}", game.record());
+}
+
+/// Prints `message` and the usage, then exits
+fn usage_error(message: &str) -> ! {
+    eprintln!("[!] {}\n{}", message, USAGE);
+    process::exit(2);
+}
 
-        let small_board_index = match input_trimmed.parse::<u8>() {
-            Ok(val) => val,
-            Err(_) => {
-                println!("[!] Input a number");
-                continue;
+/// Built-in agent or `cmd:PROGRAM ARGS` for an external engine
+fn tournament_agent(spec: &str) -> Box<dyn Agent> {
+    if let Some(command) = spec.strip_prefix("cmd:") {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        let Some((program, args)) = words.split_first() else {
+            usage_error("cmd: needs a program");
+        };
+        return match ExternalEngine::spawn(program, args) {
+            Ok(engine) => Box::new(engine),
+            Err(error) => {
+                eprintln!("[!] Can't start {}: {}", program, error);
+                process::exit(1);
             }
         };
+    }
+    match spec {
+        "human" => usage_error("humans can't play in tournaments"),
+        _ => agent_from_name(spec)
+            .unwrap_or_else(|| usage_error(&format!("Unknown agent \"{}\"", spec))),
+    }
+}
 
-        if !(1 <= small_board_index && small_board_index <= 9) {
-            println!("[!] Input a number between 1-9 (inclusive)");
-            continue;
+fn tournament(args: &[String]) {
+    let mut config = TournamentConfig {
+        games: 100,
+        limits: SearchLimits::move_time(Duration::from_millis(100)),
+        random_plies: 4,
+        seed: time_seed(),
+        sprt: None,
+    };
+    let mut agent_specs = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut number = |name: &str| -> f64 {
+            args.next()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_else(|| usage_error(&format!("{} needs a number", name)))
+        };
+        match arg.as_str() {
+            "--games" => config.games = number("--games") as u32,
+            "--movetime" => {
+                config.limits =
+                    SearchLimits::move_time(Duration::from_millis(number("--movetime") as u64))
+            }
+            "--openings" => config.random_plies = number("--openings") as u32,
+            "--seed" => config.seed = number("--seed") as u64,
+            "--sprt" => config.sprt = Some(Sprt::new(number("--sprt"), number("--sprt"))),
+            _ => agent_specs.push(arg.as_str()),
         }
+    }
+    let [first_spec, second_spec] = agent_specs[..] else {
+        usage_error("a tournament needs two agents");
+    };
+    let mut first = tournament_agent(first_spec);
+    let mut second = tournament_agent(second_spec);
+    let (first_name, second_name) = (first.name(), second.name());
 
-        let small_board_index = small_board_index - 1;
-
-        let game_status = game.make_move(big_board_index, small_board_index);
-        if let Err(error) = game_status {
-            match error {
-                BoardError::MoveAtAlreadyFilledTile => println!("[!] That tile was already taken"),
-                BoardError::MoveAtNotSentBoard => {
-                    println!("[!] You can not make a move at that board as you weren't sent there")
-                }
-                BoardError::MoveAtAlreadyFinishedBoard => {
-                    println!("[!] You can not make a move at that board as it has been completed")
-                }
-            }
-            continue;
+    let (score, sprt_status) = run_tournament(
+        [first.as_mut(), second.as_mut()],
+        &config,
+        |outcome, score| {
+            let (x, o) = if outcome.first_agent_was_x {
+                (&first_name, &second_name)
+            } else {
+                (&second_name, &first_name)
+            };
+            let result = match outcome.result {
+                WonByPlayer::X => "1-0",
+                WonByPlayer::O => "0-1",
+                _ => "1/2-1/2",
+            };
+            let forfeit = outcome
+                .forfeit
+                .map_or(String::new(), |error| format!(" ({})", error));
+            println!(
+                "[#] Game {}: {} - {} {}{}, score {} - {} - {}",
+                score.games(),
+                x,
+                o,
+                result,
+                forfeit,
+                score.wins,
+                score.losses,
+                score.draws
+            );
+        },
+    );
+
+    println!(
+        "[#] {} vs {}: {} wins, {} losses, {} draws, {:.1}% of the points",
+        first_name,
+        second_name,
+        score.wins,
+        score.losses,
+        score.draws,
+        100.0 * score.fraction()
+    );
+    match score.elo_difference() {
+        Some((elo, margin)) => println!("[#] Elo difference {:.1} +/- {:.1}", elo, margin),
+        None => println!("[#] Elo difference can't be estimated yet"),
+    }
+    if let (Some(sprt), Some(status)) = (config.sprt, sprt_status) {
+        let (lower, upper) = sprt.bounds();
+        let verdict = match status {
+            SprtStatus::Continue => "no decision yet",
+            SprtStatus::AcceptH0 => "H0 accepted",
+            SprtStatus::AcceptH1 => "H1 accepted",
+        };
+        println!(
+            "[#] SPRT [{}, {}]: LLR {:.2} ({:.2}, {:.2}), {}",
+            sprt.elo0,
+            sprt.elo1,
+            sprt.llr(&score),
+            lower,
+            upper,
+            verdict
+        );
+    }
+}
+
+fn codingame(args: &[String]) {
+    let name = match args {
+        [] => "mcts",
+        [name] if name != "human" => name.as_str(),
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
         }
+    };
+    let mut agent = agent_from_name(name).unwrap_or_else(|| {
+        eprintln!("[!] Unknown agent \"{}\"\n{}", name, USAGE);
+        process::exit(2);
+    });
+    let limits = SearchLimits::move_time(CODINGAME_MOVE_TIME);
+    if let Err(error) = play_codingame(io::stdin().lock(), io::stdout(), agent.as_mut(), &limits) {
+        eprintln!("[!] {}", error);
+        process::exit(1);
+    }
+}
 
-        print!("\x1B[2J\x1B[1;1H"); //clear screen
-        game.print_game();
-        match game_status.unwrap() {
-            uttt_game::WonByPlayer::X => {
-                println!("[#] Game won by X");
-                break;
+/// Built-in bot seeded with `seed`, MCTS on one thread so shards are reproducible
+fn selfplay_agent(name: &str, seed: u64) -> Option<Box<dyn Agent>> {
+    match name {
+        "random" => Some(Box::new(RandomAgent::new(seed))),
+        "alphabeta" => Some(Box::new(AlphaBeta::default())),
+        "mcts" => Some(Box::new(Mcts::new(
+            Mcts::DEFAULT_EXPLORATION,
+            u64::MAX,
+            seed,
+        ))),
+        _ => None,
+    }
+}
+
+fn selfplay(args: &[String]) {
+    let mut config = SelfPlayConfig {
+        shards: 1,
+        games_per_shard: 100,
+        limits: SearchLimits {
+            iterations: Some(800),
+            depth: Some(4),
+            ..SearchLimits::default()
+        },
+        sample_plies: 10,
+        seed: time_seed(),
+        threads: thread::available_parallelism().map_or(1, |threads| threads.get()),
+    };
+    let mut dir = PathBuf::from("selfplay");
+    let mut name = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut number = |name: &str| -> u64 {
+            args.next()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_else(|| usage_error(&format!("{} needs a number", name)))
+        };
+        match arg.as_str() {
+            "--shards" => config.shards = number("--shards") as u32,
+            "--games" => config.games_per_shard = number("--games") as u32,
+            "--iterations" => config.limits.iterations = Some(number("--iterations")),
+            "--depth" => config.limits.depth = Some(number("--depth") as u32),
+            "--movetime" => {
+                config.limits.move_time = Some(Duration::from_millis(number("--movetime")))
             }
-            uttt_game::WonByPlayer::O => {
-                println!("[#] Game won by O");
-                break;
+            "--sample-plies" => config.sample_plies = number("--sample-plies") as u32,
+            "--seed" => config.seed = number("--seed"),
+            "--threads" => config.threads = number("--threads") as usize,
+            "--out" => {
+                dir = args
+                    .next()
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| usage_error("--out needs a directory"))
             }
-            uttt_game::WonByPlayer::Tie => {
-                println!("[#] Game tied");
-                break;
+            _ if name.is_none() => name = Some(arg.as_str()),
+            _ => usage_error(&format!("unexpected argument \"{}\"", arg)),
+        }
+    }
+    let name = name.unwrap_or("mcts");
+    if selfplay_agent(name, 0).is_none() {
+        usage_error(&format!("Unknown self-play agent \"{}\"", name));
+    }
+
+    let shards = generate_shards(
+        &dir,
+        &config,
+        |seed| selfplay_agent(name, seed).expect("checked above"),
+        |shard| {
+            println!(
+                "[#] Shard {}: {} games, {} samples in {}",
+                shard.index,
+                shard.games,
+                shard.samples,
+                shard.path.display()
+            )
+        },
+    );
+    match shards {
+        Ok(shards) => {
+            let samples: u64 = shards.iter().map(|shard| shard.samples).sum();
+            println!(
+                "[#] {} samples of {} bytes in {} shards",
+                samples,
+                Sample::RECORD_SIZE,
+                shards.len()
+            );
+        }
+        Err(error) => {
+            eprintln!("[!] {}", error);
+            process::exit(1);
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("play") => play(&args[1..]),
+        Some("codingame") => codingame(&args[1..]),
+        Some("tournament") => tournament(&args[1..]),
+        Some("selfplay") => selfplay(&args[1..]),
+        Some("engine") => {
+            if let Err(error) = Engine::run(io::stdin().lock(), io::stdout()) {
+                eprintln!("[!] {}", error);
+                process::exit(1);
             }
-            uttt_game::WonByPlayer::HasntFinished => (),
-        };
+        }
+        Some("-h" | "--help") => println!("{}", USAGE),
+        _ => play(&args),
     }
 }
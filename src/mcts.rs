@@ -0,0 +1,518 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::Instant;
+
+use crate::agent::Agent;
+use crate::limits::{SearchLimits, StopFlag};
+use crate::moves::{LegalMoves, Move};
+use crate::rng::Rng;
+use crate::uttt_game::{Game, Player, WonByPlayer};
+
+/// Outcome of [`Mcts::search`]
+#[derive(Clone, PartialEq, Debug)]
+pub struct MctsResult {
+    /// Most visited move, `None` only if the game is already over
+    pub best_move: Option<Move>,
+    /// Visits of every legal move at the root, in [`Move::index`] order
+    pub visits: Vec<(Move, u32)>,
+    pub iterations: u64,
+    /// Average playout score for the side to move, 1 is a win and 0.5 a tie
+    pub value: f64,
+}
+
+impl MctsResult {
+    /// Root visit counts normalised to sum to 1, a policy target for training
+    pub fn policy(&self) -> Vec<(Move, f64)> {
+        let total: u32 = self.visits.iter().map(|&(_, visits)| visits).sum();
+        self.visits
+            .iter()
+            .map(|&(mv, visits)| (mv, f64::from(visits) / f64::from(total.max(1))))
+            .collect()
+    }
+
+    fn new(visits: Vec<(Move, u32)>, iterations: u64, value: f64) -> Self {
+        //ties go to the lowest index so the choice is reproducible
+        let best_move = visits
+            .iter()
+            .rev()
+            .max_by_key(|&&(_, visits)| visits)
+            .map(|&(mv, _)| mv);
+        Self {
+            best_move,
+            visits,
+            iterations,
+            value,
+        }
+    }
+}
+
+/// How [`Mcts`] spreads a search over several threads
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Parallelism {
+    /// Every thread grows its own tree with its share of the iterations,
+    /// the root visit counts are summed at the end
+    Root,
+    /// All threads grow one shared tree, a virtual loss on the nodes a thread is
+    /// working on steers the others to different lines
+    Tree,
+}
+
+/// Monte Carlo tree search with UCT selection and uniformly random playouts
+#[derive(Clone, Debug)]
+pub struct Mcts {
+    /// `c` in `score/visits + c*sqrt(ln(parent visits)/visits)`
+    pub exploration: f64,
+    /// Playouts per search when the limits don't give a number
+    pub max_iterations: u64,
+    /// With 1 (the default) the search is deterministic for a given seed
+    pub threads: usize,
+    /// Only used with more than one thread
+    pub parallelism: Parallelism,
+    rng: Rng,
+}
+
+impl Default for Mcts {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_EXPLORATION, 100_000, 0)
+    }
+}
+
+impl Mcts {
+    pub const DEFAULT_EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+    /// Single threaded search, see [`Mcts::with_threads`]
+    pub const fn new(exploration: f64, max_iterations: u64, seed: u64) -> Self {
+        Self {
+            exploration,
+            max_iterations,
+            threads: 1,
+            parallelism: Parallelism::Root,
+            rng: Rng::new(seed),
+        }
+    }
+
+    pub const fn with_threads(mut self, threads: usize, parallelism: Parallelism) -> Self {
+        self.threads = threads;
+        self.parallelism = parallelism;
+        self
+    }
+
+    /// Builds a fresh tree from `game` until `limits` (or `max_iterations`) are reached,
+    /// a stopped search still reports the visits so far. A node limit caps the playouts
+    /// like `iterations`, a depth limit ends the search once a line of the tree is that
+    /// deep or reaches the end of the game
+    pub fn search(&mut self, game: &Game, limits: &SearchLimits) -> MctsResult {
+        let stop = Stop {
+            deadline: limits.deadline(Instant::now()),
+            flag: limits.stop.clone(),
+            depth: limits.depth,
+        };
+        let max_iterations = limits
+            .iterations
+            .into_iter()
+            .chain(limits.nodes)
+            .fold(self.max_iterations, u64::min);
+        if self.threads <= 1 {
+            return search_sequential(game, self.exploration, max_iterations, &stop, &mut self.rng);
+        }
+
+        let seeds: Vec<u64> = (0..self.threads).map(|_| self.rng.next_u64()).collect();
+        match self.parallelism {
+            Parallelism::Root => self.search_root_parallel(game, max_iterations, &stop, &seeds),
+            Parallelism::Tree => self.search_tree_parallel(game, max_iterations, &stop, &seeds),
+        }
+    }
+
+    fn search_root_parallel(
+        &self,
+        game: &Game,
+        max_iterations: u64,
+        stop: &Stop,
+        seeds: &[u64],
+    ) -> MctsResult {
+        let threads = seeds.len() as u64;
+        let results: Vec<MctsResult> = thread::scope(|scope| {
+            let handles: Vec<_> = seeds
+                .iter()
+                .enumerate()
+                .map(|(i, &seed)| {
+                    let iterations =
+                        max_iterations / threads + u64::from((i as u64) < max_iterations % threads);
+                    scope.spawn(move || {
+                        search_sequential(
+                            game,
+                            self.exploration,
+                            iterations,
+                            stop,
+                            &mut Rng::new(seed),
+                        )
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+
+        //every tree has the same root, so the visit lists line up
+        let mut visits = results[0].visits.clone();
+        for result in &results[1..] {
+            for (total, &(_, visits)) in visits.iter_mut().zip(&result.visits) {
+                total.1 += visits;
+            }
+        }
+        let iterations: u64 = results.iter().map(|result| result.iterations).sum();
+        let value = if iterations == 0 {
+            0.5
+        } else {
+            results
+                .iter()
+                .map(|result| result.value * result.iterations as f64)
+                .sum::<f64>()
+                / iterations as f64
+        };
+        MctsResult::new(visits, iterations, value)
+    }
+
+    fn search_tree_parallel(
+        &self,
+        game: &Game,
+        max_iterations: u64,
+        stop: &Stop,
+        seeds: &[u64],
+    ) -> MctsResult {
+        let root = SharedNode::new(None, game);
+        let started = AtomicU64::new(0);
+        let depth_reached = AtomicBool::new(false);
+        thread::scope(|scope| {
+            for &seed in seeds {
+                let (root, started, depth_reached) = (&root, &started, &depth_reached);
+                scope.spawn(move || {
+                    let mut rng = Rng::new(seed);
+                    let mut path = Vec::with_capacity(82);
+                    let mut local_iterations = 0u64;
+                    while !game.is_finished()
+                        && started.fetch_add(1, Ordering::Relaxed) < max_iterations
+                    {
+                        if stop.reached(local_iterations) || depth_reached.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        if self.tree_parallel_iteration(root, game, &mut path, stop, &mut rng) {
+                            depth_reached.store(true, Ordering::Relaxed);
+                        }
+                        local_iterations += 1;
+                    }
+                });
+            }
+        });
+
+        let visits = match root.children.get() {
+            Some(children) => children
+                .iter()
+                .map(|child| (child.mv.unwrap(), child.visits.load(Ordering::Relaxed)))
+                .collect(),
+            None => game.legal_moves().map(|mv| (mv, 0)).collect(),
+        };
+        let root_visits = root.visits.load(Ordering::Relaxed);
+        let value = if root_visits == 0 {
+            0.5
+        } else {
+            //the root's score counts for the player who moved last
+            1.0 - root.score() / f64::from(root_visits)
+        };
+        MctsResult::new(visits, u64::from(root_visits), value)
+    }
+
+    /// One selection, expansion, playout and backpropagation on the shared tree,
+    /// returns whether it reached the depth limit of `stop`
+    fn tree_parallel_iteration<'a>(
+        &self,
+        root: &'a SharedNode,
+        game: &Game,
+        path: &mut Vec<&'a SharedNode>,
+        stop: &Stop,
+        rng: &mut Rng,
+    ) -> bool {
+        let mut game = *game;
+        let mut node = root;
+        path.clear();
+        path.push(node);
+        node.virtual_loss.fetch_add(1, Ordering::Relaxed);
+
+        //selection, a node gets its children the second time it is reached
+        while !game.is_finished() {
+            let children = match node.children.get() {
+                Some(children) => children,
+                None if path.len() > 1 && node.visits.load(Ordering::Relaxed) == 0 => break,
+                None => node.children.get_or_init(|| {
+                    game.legal_moves()
+                        .map(|mv| {
+                            let mut child_game = game;
+                            child_game.make_move(mv).unwrap();
+                            SharedNode::new(Some(mv), &child_game)
+                        })
+                        .collect()
+                }),
+            };
+            node = select_shared_child(children, node.effective_visits(), self.exploration);
+            node.virtual_loss.fetch_add(1, Ordering::Relaxed);
+            game.make_move(node.mv.unwrap()).unwrap();
+            path.push(node);
+        }
+
+        let depth_reached = stop.depth_reached(path.len() - 1, &game);
+        let result = game.random_playout(rng);
+        for node in path.iter() {
+            node.half_points
+                .fetch_add(half_points(result, node.player), Ordering::Relaxed);
+            node.visits.fetch_add(1, Ordering::Relaxed);
+            node.virtual_loss.fetch_sub(1, Ordering::Relaxed);
+        }
+        depth_reached
+    }
+}
+
+/// The parts of [`SearchLimits`] that can end a search early
+struct Stop {
+    deadline: Option<Instant>,
+    flag: Option<StopFlag>,
+    depth: Option<u32>,
+}
+
+impl Stop {
+    /// The clock is only read every so often, it is slow next to a playout
+    fn reached(&self, iterations: u64) -> bool {
+        self.flag.as_ref().is_some_and(StopFlag::is_stopped)
+            || (iterations.is_multiple_of(64)
+                && self
+                    .deadline
+                    .is_some_and(|deadline| Instant::now() >= deadline))
+    }
+
+    /// Whether a playout started `plies` below the root, in `leaf`, ends a depth
+    /// limited search. A finished game ends it too, the tree can't grow past that line
+    fn depth_reached(&self, plies: usize, leaf: &Game) -> bool {
+        self.depth
+            .is_some_and(|depth| plies >= depth as usize || leaf.is_finished())
+    }
+}
+
+struct Node {
+    /// Move leading here, `None` at the root
+    mv: Option<Move>,
+    /// Player who made `mv`, the one `score` is counted for
+    player: Player,
+    children: Vec<u32>,
+    /// Legal moves that don't have a child yet
+    untried: u128,
+    visits: u32,
+    score: f64,
+}
+
+impl Node {
+    fn new(mv: Option<Move>, game: &Game) -> Self {
+        Self {
+            mv,
+            player: game.get_next_player().opponent(),
+            children: Vec::new(),
+            untried: game.legal_move_mask(),
+            visits: 0,
+            score: 0.0,
+        }
+    }
+}
+
+/// Grows one tree on the calling thread, the same `rng` state gives the same result
+fn search_sequential(
+    game: &Game,
+    exploration: f64,
+    max_iterations: u64,
+    stop: &Stop,
+    rng: &mut Rng,
+) -> MctsResult {
+    let mut tree = vec![Node::new(None, game)];
+    let mut path = Vec::with_capacity(82);
+    let mut iterations = 0u64;
+
+    while iterations < max_iterations && !game.is_finished() {
+        if stop.reached(iterations) {
+            break;
+        }
+        let mut game = *game;
+        let mut node = 0;
+        path.clear();
+        path.push(node);
+
+        //selection
+        while tree[node].untried == 0 && !tree[node].children.is_empty() {
+            node = select_child(&tree, node, exploration);
+            game.make_move(tree[node].mv.unwrap()).unwrap();
+            path.push(node);
+        }
+
+        //expansion
+        if tree[node].untried != 0 {
+            let untried = LegalMoves::from_mask(tree[node].untried);
+            let mv = untried
+                .get(rng.below(untried.len() as u32) as usize)
+                .unwrap();
+            tree[node].untried &= !(1 << mv.index());
+            game.make_move(mv).unwrap();
+            let child = tree.len();
+            tree.push(Node::new(Some(mv), &game));
+            tree[node].children.push(child as u32);
+            node = child;
+            path.push(node);
+        }
+
+        //simulation and backpropagation
+        let depth_reached = stop.depth_reached(path.len() - 1, &game);
+        let result = game.random_playout(rng);
+        for &index in &path {
+            let node = &mut tree[index];
+            node.visits += 1;
+            node.score += reward(result, node.player);
+        }
+        iterations += 1;
+        if depth_reached {
+            break;
+        }
+    }
+
+    let root = &tree[0];
+    let mut visits: Vec<(Move, u32)> = root
+        .children
+        .iter()
+        .map(|&child| {
+            (
+                tree[child as usize].mv.unwrap(),
+                tree[child as usize].visits,
+            )
+        })
+        .chain(LegalMoves::from_mask(root.untried).map(|mv| (mv, 0)))
+        .collect();
+    visits.sort_unstable_by_key(|&(mv, _)| mv.index());
+    let value = if root.visits == 0 {
+        0.5
+    } else {
+        //root.score counts for the player who moved last
+        1.0 - root.score / f64::from(root.visits)
+    };
+    MctsResult::new(visits, iterations, value)
+}
+
+fn select_child(tree: &[Node], node: usize, exploration: f64) -> usize {
+    let log_visits = f64::from(tree[node].visits).ln();
+    let uct = |child: &Node| {
+        child.score / f64::from(child.visits)
+            + exploration * (log_visits / f64::from(child.visits)).sqrt()
+    };
+    let mut best = tree[node].children[0] as usize;
+    for &child in &tree[node].children[1..] {
+        if uct(&tree[child as usize]) > uct(&tree[best]) {
+            best = child as usize;
+        }
+    }
+    best
+}
+
+/// Node of the tree shared by the [`Parallelism::Tree`] threads, children are
+/// created all at once so threads can walk the tree without locking
+struct SharedNode {
+    mv: Option<Move>,
+    player: Player,
+    visits: AtomicU32,
+    /// Threads currently below this node, each counts as a lost playout
+    virtual_loss: AtomicU32,
+    /// Playout score for `player` in half points so ties stay integers
+    half_points: AtomicU64,
+    /// Same order as [`Game::legal_moves`]
+    children: OnceLock<Box<[SharedNode]>>,
+}
+
+impl SharedNode {
+    fn new(mv: Option<Move>, game: &Game) -> Self {
+        Self {
+            mv,
+            player: game.get_next_player().opponent(),
+            visits: AtomicU32::new(0),
+            virtual_loss: AtomicU32::new(0),
+            half_points: AtomicU64::new(0),
+            children: OnceLock::new(),
+        }
+    }
+
+    fn score(&self) -> f64 {
+        self.half_points.load(Ordering::Relaxed) as f64 / 2.0
+    }
+
+    fn effective_visits(&self) -> u32 {
+        self.visits.load(Ordering::Relaxed) + self.virtual_loss.load(Ordering::Relaxed)
+    }
+}
+
+/// UCT over visits plus virtual losses, children nobody has reached yet go first
+fn select_shared_child(
+    children: &[SharedNode],
+    parent_visits: u32,
+    exploration: f64,
+) -> &SharedNode {
+    let log_visits = f64::from(parent_visits.max(1)).ln();
+    let uct = |child: &SharedNode| {
+        let visits = child.effective_visits();
+        if visits == 0 {
+            return f64::INFINITY;
+        }
+        child.score() / f64::from(visits) + exploration * (log_visits / f64::from(visits)).sqrt()
+    };
+    let mut best = &children[0];
+    let mut best_uct = uct(best);
+    for child in &children[1..] {
+        let child_uct = uct(child);
+        if child_uct > best_uct {
+            best = child;
+            best_uct = child_uct;
+        }
+    }
+    best
+}
+
+/// Playout score for `player`
+fn reward(result: WonByPlayer, player: Player) -> f64 {
+    match (result, player) {
+        (WonByPlayer::X, Player::X) | (WonByPlayer::O, Player::O) => 1.0,
+        (WonByPlayer::X, Player::O) | (WonByPlayer::O, Player::X) => 0.0,
+        _ => 0.5,
+    }
+}
+
+/// [`reward`] doubled
+fn half_points(result: WonByPlayer, player: Player) -> u64 {
+    match (result, player) {
+        (WonByPlayer::X, Player::X) | (WonByPlayer::O, Player::O) => 2,
+        (WonByPlayer::X, Player::O) | (WonByPlayer::O, Player::X) => 0,
+        _ => 1,
+    }
+}
+
+impl Agent for Mcts {
+    fn choose_move(&mut self, game: &Game, limits: &SearchLimits) -> Option<Move> {
+        self.search(game, limits).best_move
+    }
+
+    /// The policy is the root visit distribution, see [`MctsResult::policy`]
+    fn choose_move_with_policy(
+        &mut self,
+        game: &Game,
+        limits: &SearchLimits,
+    ) -> Option<(Move, Vec<(Move, f64)>)> {
+        let result = self.search(game, limits);
+        Some((result.best_move?, result.policy()))
+    }
+
+    fn name(&self) -> String {
+        "mcts".to_owned()
+    }
+}
@@ -0,0 +1,169 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::uttt_game::BoardError;
+
+/// A move, always in range: small board `0-8` and tile `0-8` inside it, both row by row
+///
+/// On the 9x9 grid of tiles row `0` is the top row and column `0` the leftmost one
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
+pub struct Move {
+    // 9*small_board_pos + tile_pos, same as the bit in a legal move mask
+    index: u8,
+}
+
+impl Move {
+    pub const fn new(small_board_pos: u8, tile_pos: u8) -> Result<Self, BoardError> {
+        if small_board_pos > 8 || tile_pos > 8 {
+            return Err(BoardError::IndexOutOfRange {
+                small_board_pos,
+                tile_pos,
+            });
+        }
+        Ok(Self {
+            index: 9 * small_board_pos + tile_pos,
+        })
+    }
+
+    /// Move from its tile's `row` and `col` on the 9x9 grid (both 0-8)
+    pub const fn from_row_col(row: u8, col: u8) -> Result<Self, BoardError> {
+        if row > 8 || col > 8 {
+            return Err(BoardError::RowColOutOfRange { row, col });
+        }
+        Ok(Self::from_index_unchecked(
+            9 * (row / 3 * 3 + col / 3) + row % 3 * 3 + col % 3,
+        ))
+    }
+
+    /// Move from `9*small_board_pos + tile_pos` (0-80)
+    pub const fn from_index(index: u8) -> Option<Self> {
+        if index > 80 {
+            return None;
+        }
+        Some(Self::from_index_unchecked(index))
+    }
+
+    pub(crate) const fn from_index_unchecked(index: u8) -> Self {
+        Self { index }
+    }
+
+    pub const fn small_board_pos(self) -> u8 {
+        self.index / 9
+    }
+
+    pub const fn tile_pos(self) -> u8 {
+        self.index % 9
+    }
+
+    /// `9*small_board_pos + tile_pos`, the bit of this move in a legal move mask
+    pub const fn index(self) -> u8 {
+        self.index
+    }
+
+    /// Row of the tile on the 9x9 grid
+    pub const fn row(self) -> u8 {
+        self.small_board_pos() / 3 * 3 + self.tile_pos() / 3
+    }
+
+    /// Column of the tile on the 9x9 grid
+    pub const fn col(self) -> u8 {
+        self.small_board_pos() % 3 * 3 + self.tile_pos() % 3
+    }
+}
+
+impl Display for Move {
+    /// Canonical notation, the inverse of [`Move::from_str`]: column `a-i` then row `1-9`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", char::from(b'a' + self.col()), self.row() + 1)
+    }
+}
+
+/// Returned when a string isn't a valid move
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParseMoveError {
+    input: String,
+}
+
+impl Display for ParseMoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid move \"{}\", expected a column a-i followed by a row 1-9 like \"e5\"",
+            self.input
+        )
+    }
+}
+
+impl std::error::Error for ParseMoveError {}
+
+impl FromStr for Move {
+    type Err = ParseMoveError;
+
+    /// Parses a tile of the 9x9 grid written as its column `a-i` (left to right)
+    /// followed by its row `1-9` (top to bottom), e.g. `e5` is the very centre
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseMoveError {
+            input: s.to_owned(),
+        };
+        match s.trim().as_bytes() {
+            &[col @ b'a'..=b'i', row @ b'1'..=b'9'] => {
+                Move::from_row_col(row - b'1', col - b'a').map_err(|_| error())
+            }
+            _ => Err(error()),
+        }
+    }
+}
+
+/// Iterator over a legal move bitmask as returned by [`Game::legal_move_mask`](crate::Game::legal_move_mask)
+///
+/// Bit [`Move::index`] stands for that move, moves are yielded in ascending order
+#[derive(Clone, Copy, Debug)]
+pub struct LegalMoves {
+    mask: u128,
+}
+
+impl LegalMoves {
+    pub const fn from_mask(mask: u128) -> Self {
+        Self { mask }
+    }
+
+    pub const fn mask(&self) -> u128 {
+        self.mask
+    }
+
+    /// `n`th remaining move without consuming the iterator, useful for sampling
+    pub fn get(&self, n: usize) -> Option<Move> {
+        let mut mask = self.mask;
+        for _ in 0..n {
+            if mask == 0 {
+                return None;
+            }
+            mask &= mask - 1;
+        }
+        Self::lowest(mask)
+    }
+
+    fn lowest(mask: u128) -> Option<Move> {
+        if mask == 0 {
+            return None;
+        }
+        Some(Move::from_index_unchecked(mask.trailing_zeros() as u8))
+    }
+}
+
+impl Iterator for LegalMoves {
+    type Item = Move;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = Self::lowest(self.mask)?;
+        self.mask &= self.mask - 1; //clear lowest set bit
+        Some(next)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let count = self.mask.count_ones() as usize;
+        (count, Some(count))
+    }
+}
+
+impl ExactSizeIterator for LegalMoves {}
@@ -0,0 +1,28 @@
+use crate::uttt_game::Game;
+
+impl Game {
+    /// Number of move sequences of exactly `depth` moves from this position (leaf nodes of
+    /// the legal move tree), games that end earlier don't count. Meant for rules regression
+    /// tests, the known values from the initial position are in `tests/perft.rs`
+    pub fn perft(&self, depth: u32) -> u64 {
+        let mut game = *self;
+        game.perft_in_place(depth)
+    }
+
+    fn perft_in_place(&mut self, depth: u32) -> u64 {
+        let moves = self.legal_moves();
+        match depth {
+            0 => 1,
+            //bulk count, no need to make the last moves
+            1 => moves.len() as u64,
+            _ => moves
+                .map(|mv| {
+                    self.make_move(mv).unwrap();
+                    let nodes = self.perft_in_place(depth - 1);
+                    self.unmake_move();
+                    nodes
+                })
+                .sum(),
+        }
+    }
+}
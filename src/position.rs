@@ -0,0 +1,269 @@
+use std::fmt::Display;
+
+use crate::uttt_game::{has_line, Game, Mark, Player};
+
+/// Returned by [`Game::from_position`]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PositionError {
+    /// Not three space separated fields
+    WrongFieldCount,
+    /// The tile field isn't nine `/` separated rows of nine tiles
+    InvalidTiles,
+    InvalidSideToMove,
+    InvalidSentBoard,
+    /// X moves first, so X has as many tiles as O when X is to move and one more otherwise
+    TileCountMismatch {
+        x_tiles: u8,
+        o_tiles: u8,
+        next_player: Player,
+    },
+    /// Both players completed a line on a small board, or on the big board if `None`
+    BothPlayersWon {
+        small_board_pos: Option<u8>,
+    },
+    /// A line on a small board, or on the big board if `None`, that no single move could
+    /// have completed: without any one of the winner's tiles there is still a line
+    ImpossibleWin {
+        small_board_pos: Option<u8>,
+    },
+    /// The previous player has no tile at `sent_board_index` on any board, so no move
+    /// could have sent the next player there
+    UnreachableSentBoard {
+        sent_board_index: u8,
+    },
+    /// Tiles have been placed but no sent board is given
+    MissingSentBoard,
+}
+
+impl Display for PositionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::WrongFieldCount => write!(f, "expected tiles, side to move and sent board"),
+            Self::InvalidTiles => write!(f, "expected nine rows of nine tiles separated by '/'"),
+            Self::InvalidSideToMove => write!(f, "side to move has to be 'x' or 'o'"),
+            Self::InvalidSentBoard => write!(f, "sent board has to be '-' or a board index 0-8"),
+            Self::TileCountMismatch {
+                x_tiles,
+                o_tiles,
+                next_player,
+            } => write!(
+                f,
+                "{} X and {} O tiles can't have {} to move",
+                x_tiles,
+                o_tiles,
+                next_player.get_letter()
+            ),
+            Self::BothPlayersWon {
+                small_board_pos: Some(small_board_pos),
+            } => write!(
+                f,
+                "both players have a line on board #{}",
+                small_board_pos + 1
+            ),
+            Self::BothPlayersWon {
+                small_board_pos: None,
+            } => {
+                write!(f, "both players have a line on the big board")
+            }
+            Self::ImpossibleWin {
+                small_board_pos: Some(small_board_pos),
+            } => write!(
+                f,
+                "board #{} was won with tiles to spare, it would have finished earlier",
+                small_board_pos + 1
+            ),
+            Self::ImpossibleWin {
+                small_board_pos: None,
+            } => write!(
+                f,
+                "the big board was won with boards to spare, the game would have ended earlier"
+            ),
+            Self::UnreachableSentBoard { sent_board_index } => write!(
+                f,
+                "no move of the previous player could have sent to board #{}",
+                sent_board_index + 1
+            ),
+            Self::MissingSentBoard => {
+                write!(f, "tiles have been placed but no sent board is given")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PositionError {}
+
+/// Whether the squares in `bits` are either without a line or have one square whose
+/// removal leaves none, i.e. the board was still open before that last square was taken.
+/// Once a board is won nobody can play on it, so every other case can't be reached
+fn could_be_won_by_one_move(bits: u16) -> bool {
+    !has_line(bits) || (0..9).any(|i| bits & 1 << i != 0 && !has_line(bits & !(1 << i)))
+}
+
+/// Parses one row of the 9x9 grid, `x`, `o` or a digit for that many empty tiles
+fn parse_row(row: &str) -> Option<[Mark; 9]> {
+    let mut marks = [Mark::Empty; 9];
+    let mut col = 0;
+    for c in row.chars() {
+        match c {
+            'x' | 'o' if col < 9 => {
+                marks[col] = if c == 'x' { Mark::X } else { Mark::O };
+                col += 1;
+            }
+            '1'..='9' => col += c.to_digit(10).unwrap() as usize,
+            _ => return None,
+        }
+    }
+    (col == 9).then_some(marks)
+}
+
+impl Game {
+    /// The position as one line: the 9x9 grid row by row from the top, side to move
+    /// and the board the last move sent to (the raw [`Game::last_sent_board_index`]), e.g.
+    /// `9/9/9/9/4x4/9/9/9/9 o 4` after X played the very centre
+    ///
+    /// Tiles are `x`, `o` or a digit for a run of empty tiles, rows are separated by `/`.
+    /// The sent board is `-` before the first move
+    pub fn position(&self) -> String {
+        let big_board = self.get_big_board();
+        let mut tiles = String::new();
+        for row in 0..9 {
+            if row > 0 {
+                tiles.push('/');
+            }
+            let mut empty_run = 0;
+            for col in 0..9 {
+                let small_board = big_board.get_small_board(row / 3 * 3 + col / 3);
+                let c = match small_board.get_mark(row % 3 * 3 + col % 3) {
+                    Mark::X => 'x',
+                    Mark::O => 'o',
+                    Mark::Empty => {
+                        empty_run += 1;
+                        continue;
+                    }
+                };
+                if empty_run > 0 {
+                    tiles += &empty_run.to_string();
+                    empty_run = 0;
+                }
+                tiles.push(c);
+            }
+            if empty_run > 0 {
+                tiles += &empty_run.to_string();
+            }
+        }
+
+        let side = match self.get_next_player() {
+            Player::X => 'x',
+            Player::O => 'o',
+        };
+        let sent_board = match self.last_sent_board_index() {
+            Some(index) => index.to_string(),
+            None => "-".to_owned(),
+        };
+        format!("{} {} {}", tiles, side, sent_board)
+    }
+
+    /// Sets up a game at a position written by [`Game::position`], checking that it
+    /// could have come up in a real game. The game starts without history, so
+    /// [`Game::unmake_move`] can only take back moves made after this
+    pub fn from_position(position: &str) -> Result<Self, PositionError> {
+        let fields: Vec<&str> = position.split_whitespace().collect();
+        let [tiles, side, sent_board] = fields[..] else {
+            return Err(PositionError::WrongFieldCount);
+        };
+
+        let rows: Vec<&str> = tiles.split('/').collect();
+        if rows.len() != 9 {
+            return Err(PositionError::InvalidTiles);
+        }
+        let mut marks = [[Mark::Empty; 9]; 9];
+        for (row, row_marks) in rows.iter().enumerate() {
+            let row_marks = parse_row(row_marks).ok_or(PositionError::InvalidTiles)?;
+            for (col, mark) in row_marks.into_iter().enumerate() {
+                marks[row / 3 * 3 + col / 3][row % 3 * 3 + col % 3] = mark;
+            }
+        }
+
+        let next_player = match side {
+            "x" => Player::X,
+            "o" => Player::O,
+            _ => return Err(PositionError::InvalidSideToMove),
+        };
+        let last_sent_board_index = match sent_board {
+            "-" => None,
+            index => match index.parse::<u8>() {
+                Ok(index) if index < 9 => Some(index),
+                _ => return Err(PositionError::InvalidSentBoard),
+            },
+        };
+
+        let count = |mark| marks.iter().flatten().filter(|&&m| m == mark).count() as u8;
+        let (x_tiles, o_tiles) = (count(Mark::X), count(Mark::O));
+        let expected_x_tiles = match next_player {
+            Player::X => o_tiles,
+            Player::O => o_tiles + 1,
+        };
+        if x_tiles != expected_x_tiles {
+            return Err(PositionError::TileCountMismatch {
+                x_tiles,
+                o_tiles,
+                next_player,
+            });
+        }
+
+        let bits = |board: &[Mark; 9], mark| {
+            (0..9)
+                .filter(|&i| board[i] == mark)
+                .fold(0u16, |bits, i| bits | 1 << i)
+        };
+        let (mut x_boards, mut o_boards) = (0u16, 0u16);
+        for (small_board_pos, board) in (0u8..).zip(marks.iter()) {
+            let x_won = has_line(bits(board, Mark::X));
+            let o_won = has_line(bits(board, Mark::O));
+            if x_won && o_won {
+                return Err(PositionError::BothPlayersWon {
+                    small_board_pos: Some(small_board_pos),
+                });
+            }
+            if !could_be_won_by_one_move(bits(board, Mark::X))
+                || !could_be_won_by_one_move(bits(board, Mark::O))
+            {
+                return Err(PositionError::ImpossibleWin {
+                    small_board_pos: Some(small_board_pos),
+                });
+            }
+            x_boards |= u16::from(x_won) << small_board_pos;
+            o_boards |= u16::from(o_won) << small_board_pos;
+        }
+        if has_line(x_boards) && has_line(o_boards) {
+            return Err(PositionError::BothPlayersWon {
+                small_board_pos: None,
+            });
+        }
+        if !could_be_won_by_one_move(x_boards) || !could_be_won_by_one_move(o_boards) {
+            return Err(PositionError::ImpossibleWin {
+                small_board_pos: None,
+            });
+        }
+
+        match last_sent_board_index {
+            None if x_tiles > 0 => return Err(PositionError::MissingSentBoard),
+            Some(sent_board_index) => {
+                //the last move was made by the other player on tile sent_board_index of some board
+                let previous_mark = match next_player {
+                    Player::X => Mark::O,
+                    Player::O => Mark::X,
+                };
+                if !marks
+                    .iter()
+                    .any(|board| board[usize::from(sent_board_index)] == previous_mark)
+                {
+                    return Err(PositionError::UnreachableSentBoard { sent_board_index });
+                }
+            }
+            None => (),
+        }
+
+        Ok(Self::from_marks(&marks, next_player, last_sent_board_index))
+    }
+}
@@ -0,0 +1,130 @@
+use std::fmt::Display;
+
+use crate::moves::{Move, ParseMoveError};
+use crate::uttt_game::{BoardError, Game, WonByPlayer};
+
+/// Returned by [`Game::from_record`]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum RecordError {
+    InvalidMove {
+        ply: usize,
+        error: ParseMoveError,
+    },
+    IllegalMove {
+        ply: usize,
+        error: BoardError,
+    },
+    /// The result token doesn't match the result of replaying the moves
+    ResultMismatch {
+        recorded: WonByPlayer,
+        actual: WonByPlayer,
+    },
+    TokenAfterResult {
+        token: String,
+    },
+}
+
+impl Display for RecordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidMove { ply, error } => write!(f, "move {}: {}", ply + 1, error),
+            Self::IllegalMove { ply, error } => write!(f, "move {}: {}", ply + 1, error),
+            Self::ResultMismatch { recorded, actual } => write!(
+                f,
+                "record says the result is {} but the moves give {}",
+                result_token(*recorded),
+                result_token(*actual)
+            ),
+            Self::TokenAfterResult { token } => {
+                write!(f, "unexpected \"{}\" after the result", token)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RecordError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidMove { error, .. } => Some(error),
+            Self::IllegalMove { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+const fn result_token(result: WonByPlayer) -> &'static str {
+    match result {
+        WonByPlayer::X => "1-0",
+        WonByPlayer::O => "0-1",
+        WonByPlayer::Tie => "1/2-1/2",
+        WonByPlayer::HasntFinished => "*",
+    }
+}
+
+fn parse_result_token(token: &str) -> Option<WonByPlayer> {
+    match token {
+        "1-0" => Some(WonByPlayer::X),
+        "0-1" => Some(WonByPlayer::O),
+        "1/2-1/2" => Some(WonByPlayer::Tie),
+        "*" => Some(WonByPlayer::HasntFinished),
+        _ => None,
+    }
+}
+
+/// Move numbers like `12.`
+fn is_move_number(token: &str) -> bool {
+    token
+        .strip_suffix('.')
+        .is_some_and(|number| !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()))
+}
+
+impl Game {
+    /// The moves played so far as a one line record: moves in [`Move`] notation with
+    /// PGN-like move numbers and a result token, e.g. `1. e5 e4 2. e6 e8 *`
+    ///
+    /// Results are written as `1-0` (X won), `0-1` (O won), `1/2-1/2` (tie) or `*` (unfinished)
+    pub fn record(&self) -> String {
+        let mut record = String::new();
+        for (ply, mv) in self.history().iter().enumerate() {
+            if ply % 2 == 0 {
+                record += &format!("{}. ", ply / 2 + 1);
+            }
+            record += &format!("{} ", mv);
+        }
+        record + result_token(self.result())
+    }
+
+    /// Replays a record written by [`Game::record`] from the initial position,
+    /// move numbers are optional and the result token, if present, has to match
+    pub fn from_record(record: &str) -> Result<Self, RecordError> {
+        let mut game = Game::new();
+        let mut tokens = record.split_whitespace();
+        while let Some(token) = tokens.next() {
+            if is_move_number(token) {
+                continue;
+            }
+            if let Some(recorded) = parse_result_token(token) {
+                if recorded != game.result() {
+                    return Err(RecordError::ResultMismatch {
+                        recorded,
+                        actual: game.result(),
+                    });
+                }
+                if let Some(token) = tokens.next() {
+                    return Err(RecordError::TokenAfterResult {
+                        token: token.to_owned(),
+                    });
+                }
+                break;
+            }
+
+            let ply = game.history().len();
+            let mv = token
+                .parse::<Move>()
+                .map_err(|error| RecordError::InvalidMove { ply, error })?;
+            game.make_move(mv)
+                .map_err(|error| RecordError::IllegalMove { ply, error })?;
+        }
+        Ok(game)
+    }
+}
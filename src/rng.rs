@@ -0,0 +1,38 @@
+/// One splitmix64 step, returns the next state and the output for it
+pub(crate) const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (state, z ^ (z >> 31))
+}
+
+/// Small seeded PRNG (splitmix64) so playouts and searches are reproducible,
+/// not suitable for anything security related
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let (state, output) = splitmix64(self.state);
+        self.state = state;
+        output
+    }
+
+    /// Uniform in `0..bound`, `bound` has to be non zero
+    pub fn below(&mut self, bound: u32) -> u32 {
+        // multiply-shift, the bias for bounds this small is negligible
+        (((self.next_u64() >> 32) * u64::from(bound)) >> 32) as u32
+    }
+
+    /// Uniform in `[0, 1)`
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
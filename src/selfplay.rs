@@ -0,0 +1,317 @@
+use std::fmt::Display;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use crate::agent::{Agent, AgentError};
+use crate::limits::SearchLimits;
+use crate::moves::Move;
+use crate::rng::{splitmix64, Rng};
+use crate::uttt_game::{Game, Mark, Player, WonByPlayer};
+
+/// Length of [`encode_state`]'s output
+pub const STATE_SIZE: usize = 91;
+
+/// Encodes `game` from the point of view of the side to move: one byte per tile by
+/// move index (0 empty, 1 side to move, 2 opponent), one per small board (0 open,
+/// 1 won by the side to move, 2 won by the opponent, 3 tied) and the side to move
+/// (0 X, 1 O)
+pub fn encode_state(game: &Game) -> [u8; STATE_SIZE] {
+    let us = *game.get_next_player();
+    let player_byte = |player: Player| if player == us { 1 } else { 2 };
+    let mut state = [0; STATE_SIZE];
+    for small_board_pos in 0..9u8 {
+        let board = game.get_big_board().get_small_board(small_board_pos);
+        for tile_pos in 0..9u8 {
+            state[usize::from(9 * small_board_pos + tile_pos)] = match board.get_mark(tile_pos) {
+                Mark::X => player_byte(Player::X),
+                Mark::O => player_byte(Player::O),
+                Mark::Empty => 0,
+            };
+        }
+        state[81 + usize::from(small_board_pos)] = match board.won_by() {
+            WonByPlayer::X => player_byte(Player::X),
+            WonByPlayer::O => player_byte(Player::O),
+            WonByPlayer::Tie => 3,
+            WonByPlayer::HasntFinished => 0,
+        };
+    }
+    state[90] = match us {
+        Player::X => 0,
+        Player::O => 1,
+    };
+    state
+}
+
+/// One position of a self-play game with its training targets
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Sample {
+    /// See [`encode_state`]
+    pub state: [u8; STATE_SIZE],
+    /// Bit `Move::index` is set for every legal move
+    pub legal_mask: u128,
+    /// Probability the agent gave each move, by move index
+    pub policy: [f32; 81],
+    /// 1 if the side to move went on to win, -1 if it lost and 0 for a tie
+    pub outcome: i8,
+}
+
+impl Sample {
+    /// Bytes [`Sample::write_to`] writes: the state, the legal mask as 81 bytes of 0 or 1,
+    /// the policy as 81 little endian `f32`s and the outcome as an `i8`
+    pub const RECORD_SIZE: usize = STATE_SIZE + 81 + 4 * 81 + 1;
+
+    /// Writes the fixed size record, so a shard can be read back as one flat array
+    pub fn write_to(&self, output: &mut impl Write) -> io::Result<()> {
+        let mut record = Vec::with_capacity(Self::RECORD_SIZE);
+        record.extend_from_slice(&self.state);
+        record.extend((0..81).map(|index| (self.legal_mask >> index & 1) as u8));
+        for probability in self.policy {
+            record.extend_from_slice(&probability.to_le_bytes());
+        }
+        record.extend_from_slice(&self.outcome.to_le_bytes());
+        output.write_all(&record)
+    }
+
+    /// Reads a record written by [`Sample::write_to`], `None` at the end of input
+    pub fn read_from(input: &mut impl Read) -> io::Result<Option<Self>> {
+        let mut record = [0; Self::RECORD_SIZE];
+        match input.read_exact(&mut record) {
+            Ok(()) => (),
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(error) => return Err(error),
+        }
+        let (state, rest) = record.split_at(STATE_SIZE);
+        let (legal, rest) = rest.split_at(81);
+        let (policy, outcome) = rest.split_at(4 * 81);
+        let mut sample = Self {
+            state: state.try_into().unwrap(),
+            legal_mask: 0,
+            policy: [0.0; 81],
+            outcome: i8::from_le_bytes([outcome[0]]),
+        };
+        for (index, &legal) in legal.iter().enumerate() {
+            sample.legal_mask |= u128::from(legal != 0) << index;
+        }
+        for (probability, bytes) in sample.policy.iter_mut().zip(policy.chunks_exact(4)) {
+            *probability = f32::from_le_bytes(bytes.try_into().unwrap());
+        }
+        Ok(Some(sample))
+    }
+}
+
+/// Plays one game of `agent` against itself and returns a sample for every position
+/// it moved in. The first `sample_plies` moves are drawn from the agent's policy
+/// rather than being its best move, so games from the same agent differ. Agents
+/// without a real policy (all of it on one move) play a uniformly random legal move
+/// there instead, their sample still gets the one-hot policy
+pub fn play_self_play_game(
+    agent: &mut dyn Agent,
+    limits: &SearchLimits,
+    sample_plies: u32,
+    rng: &mut Rng,
+) -> Result<Vec<Sample>, AgentError> {
+    let mut game = Game::new();
+    let mut samples = Vec::new();
+    let mut players = Vec::new();
+    while !game.is_finished() {
+        //the agent plays both sides, its index only tells which one failed
+        let agent_index = match game.get_next_player() {
+            Player::X => 0,
+            Player::O => 1,
+        };
+        let (best_move, policy) = agent
+            .choose_move_with_policy(&game, limits)
+            .ok_or(AgentError::GaveUp { agent_index })?;
+        let mut sample = Sample {
+            state: encode_state(&game),
+            legal_mask: game.legal_move_mask(),
+            policy: [0.0; 81],
+            outcome: 0,
+        };
+        for &(mv, probability) in &policy {
+            sample.policy[usize::from(mv.index())] = probability as f32;
+        }
+
+        let mv = if (samples.len() as u32) >= sample_plies {
+            best_move
+        } else if policy.len() > 1 {
+            sample_move(&policy, rng).unwrap_or(best_move)
+        } else {
+            let moves = game.legal_moves();
+            moves
+                .get(rng.below(moves.len() as u32) as usize)
+                .unwrap_or(best_move)
+        };
+        samples.push(sample);
+        players.push(*game.get_next_player());
+        game.make_move(mv).map_err(|_| AgentError::IllegalMove {
+            agent_index,
+            attempted: mv,
+        })?;
+    }
+
+    let winner = match game.result() {
+        WonByPlayer::X => Some(Player::X),
+        WonByPlayer::O => Some(Player::O),
+        _ => None,
+    };
+    for (sample, player) in samples.iter_mut().zip(players) {
+        sample.outcome = match winner {
+            Some(winner) if winner == player => 1,
+            Some(_) => -1,
+            None => 0,
+        };
+    }
+    Ok(samples)
+}
+
+/// Move drawn with the probabilities of `policy`, `None` if it is empty
+fn sample_move(policy: &[(Move, f64)], rng: &mut Rng) -> Option<Move> {
+    let total: f64 = policy.iter().map(|&(_, probability)| probability).sum();
+    let mut target = rng.next_f64() * total;
+    for &(mv, probability) in policy {
+        if target < probability {
+            return Some(mv);
+        }
+        target -= probability;
+    }
+    //rounding can leave a sliver past the last move
+    policy.last().map(|&(mv, _)| mv)
+}
+
+/// How [`generate_shards`] plays and splits its games
+#[derive(Clone, Debug)]
+pub struct SelfPlayConfig {
+    pub shards: u32,
+    pub games_per_shard: u32,
+    pub limits: SearchLimits,
+    /// See [`play_self_play_game`]
+    pub sample_plies: u32,
+    /// Each shard gets its own seed derived from this one, so a shard comes out the
+    /// same no matter how many threads generate them
+    pub seed: u64,
+    /// Shards generated at the same time, each with its own agent
+    pub threads: usize,
+}
+
+/// A finished shard file
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Shard {
+    pub index: u32,
+    pub path: PathBuf,
+    pub games: u32,
+    pub samples: u64,
+}
+
+/// Returned by [`generate_shards`]
+#[derive(Debug)]
+pub enum SelfPlayError {
+    Io(io::Error),
+    Agent(AgentError),
+}
+
+impl Display for SelfPlayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{}", error),
+            Self::Agent(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for SelfPlayError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Agent(error) => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for SelfPlayError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<AgentError> for SelfPlayError {
+    fn from(error: AgentError) -> Self {
+        Self::Agent(error)
+    }
+}
+
+/// File name of shard `index` of a run with `seed`
+pub fn shard_file_name(seed: u64, index: u32) -> String {
+    format!("selfplay-{}-{:04}.bin", seed, index)
+}
+
+/// Plays `config.shards * config.games_per_shard` self-play games and writes their
+/// samples to one file per shard in `dir`, see [`Sample::write_to`]. `make_agent`
+/// builds the agent for a shard from a seed, `after_shard` sees every finished shard.
+/// Returns the shards by index
+pub fn generate_shards(
+    dir: &Path,
+    config: &SelfPlayConfig,
+    make_agent: impl Fn(u64) -> Box<dyn Agent> + Sync,
+    after_shard: impl Fn(&Shard) + Sync,
+) -> Result<Vec<Shard>, SelfPlayError> {
+    fs::create_dir_all(dir)?;
+    let threads = config.threads.clamp(1, config.shards.max(1) as usize);
+    let mut shards = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|thread_index| {
+                let (make_agent, after_shard) = (&make_agent, &after_shard);
+                scope.spawn(move || {
+                    let mut shards = Vec::new();
+                    for index in (thread_index as u32..config.shards).step_by(threads) {
+                        let shard = write_shard(dir, config, index, make_agent)?;
+                        after_shard(&shard);
+                        shards.push(shard);
+                    }
+                    Ok::<_, SelfPlayError>(shards)
+                })
+            })
+            .collect();
+        let mut shards = Vec::new();
+        for worker in workers {
+            shards.extend(worker.join().expect("self-play thread panicked")?);
+        }
+        Ok::<_, SelfPlayError>(shards)
+    })?;
+    shards.sort_unstable_by_key(|shard| shard.index);
+    Ok(shards)
+}
+
+fn write_shard(
+    dir: &Path,
+    config: &SelfPlayConfig,
+    index: u32,
+    make_agent: impl Fn(u64) -> Box<dyn Agent>,
+) -> Result<Shard, SelfPlayError> {
+    let mut rng = Rng::new(splitmix64(config.seed ^ u64::from(index)).1);
+    let mut agent = make_agent(rng.next_u64());
+    let path = dir.join(shard_file_name(config.seed, index));
+    let mut output = BufWriter::new(File::create(&path)?);
+    let mut samples = 0;
+    for _ in 0..config.games_per_shard {
+        let game_samples = play_self_play_game(
+            agent.as_mut(),
+            &config.limits,
+            config.sample_plies,
+            &mut rng,
+        )?;
+        for sample in &game_samples {
+            sample.write_to(&mut output)?;
+        }
+        samples += game_samples.len() as u64;
+    }
+    output.flush()?;
+    Ok(Shard {
+        index,
+        path,
+        games: config.games_per_shard,
+        samples,
+    })
+}
@@ -0,0 +1,238 @@
+use crate::agent::{play_game, Agent, AgentError};
+use crate::limits::SearchLimits;
+use crate::rng::Rng;
+use crate::uttt_game::{Game, WonByPlayer};
+
+/// Games from the point of view of the first agent of a match
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Debug)]
+pub struct Score {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+impl Score {
+    pub const fn games(&self) -> u32 {
+        self.wins + self.losses + self.draws
+    }
+
+    /// Points per game, a draw is half a point
+    pub fn fraction(&self) -> f64 {
+        if self.games() == 0 {
+            return 0.5;
+        }
+        (f64::from(self.wins) + f64::from(self.draws) / 2.0) / f64::from(self.games())
+    }
+
+    /// Variance of the points of a single game
+    fn variance(&self) -> f64 {
+        let fraction = self.fraction();
+        (f64::from(self.wins) * (1.0 - fraction).powi(2)
+            + f64::from(self.draws) * (0.5 - fraction).powi(2)
+            + f64::from(self.losses) * fraction.powi(2))
+            / f64::from(self.games())
+    }
+
+    /// Elo difference and the half width of its 95% confidence interval, `None`
+    /// before the first game and while one side has every point
+    pub fn elo_difference(&self) -> Option<(f64, f64)> {
+        let fraction = self.fraction();
+        if self.games() == 0 || fraction <= 0.0 || fraction >= 1.0 {
+            return None;
+        }
+        let standard_error = (self.variance() / f64::from(self.games())).sqrt();
+        //the interval is symmetric in points, not in Elo, so average its two halves
+        let low = elo_from_fraction((fraction - 1.96 * standard_error).max(f64::MIN_POSITIVE));
+        let high = elo_from_fraction((fraction + 1.96 * standard_error).min(1.0 - f64::EPSILON));
+        Some((elo_from_fraction(fraction), (high - low) / 2.0))
+    }
+}
+
+/// Elo difference that gives an expected `fraction` of the points
+pub fn elo_from_fraction(fraction: f64) -> f64 {
+    -400.0 * (1.0 / fraction - 1.0).log10()
+}
+
+/// Expected fraction of the points at an Elo difference of `elo`
+pub fn fraction_from_elo(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+/// Sequential probability ratio test of H0 "the first agent is `elo0` stronger"
+/// against H1 "it is `elo1` stronger", with error rates `alpha` and `beta`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Sprt {
+    pub elo0: f64,
+    pub elo1: f64,
+    pub alpha: f64,
+    pub beta: f64,
+}
+
+/// Where an [`Sprt`] stands after some games
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SprtStatus {
+    Continue,
+    AcceptH0,
+    AcceptH1,
+}
+
+impl Sprt {
+    /// H0 elo0, H1 elo1 with 5% error rates
+    pub const fn new(elo0: f64, elo1: f64) -> Self {
+        Self {
+            elo0,
+            elo1,
+            alpha: 0.05,
+            beta: 0.05,
+        }
+    }
+
+    /// Log-likelihood ratio of H1 to H0 under the normal approximation
+    pub fn llr(&self, score: &Score) -> f64 {
+        if score.games() == 0 {
+            return 0.0;
+        }
+        //with every game ending the same way the variance is 0, a virtual draw keeps
+        //the ratio finite so a one-sided match still gets decided
+        let score = if score.variance() == 0.0 {
+            Score {
+                draws: score.draws + 1,
+                ..*score
+            }
+        } else {
+            *score
+        };
+        let (fraction0, fraction1) = (fraction_from_elo(self.elo0), fraction_from_elo(self.elo1));
+        (fraction1 - fraction0)
+            * (2.0 * score.fraction() - fraction0 - fraction1)
+            * f64::from(score.games())
+            / (2.0 * score.variance())
+    }
+
+    /// The log-likelihood ratio bounds for accepting H0 and H1
+    pub fn bounds(&self) -> (f64, f64) {
+        (
+            (self.beta / (1.0 - self.alpha)).ln(),
+            ((1.0 - self.beta) / self.alpha).ln(),
+        )
+    }
+
+    pub fn status(&self, score: &Score) -> SprtStatus {
+        let llr = self.llr(score);
+        let (lower, upper) = self.bounds();
+        if llr >= upper {
+            SprtStatus::AcceptH1
+        } else if llr <= lower {
+            SprtStatus::AcceptH0
+        } else {
+            SprtStatus::Continue
+        }
+    }
+}
+
+/// How a match is played
+#[derive(Clone, Debug)]
+pub struct TournamentConfig {
+    pub games: u32,
+    pub limits: SearchLimits,
+    /// Random moves played before the agents take over, each opening is used for two
+    /// games with the colours swapped so deterministic agents still play different games
+    pub random_plies: u32,
+    pub seed: u64,
+    /// Stops the match early once the test has an answer
+    pub sprt: Option<Sprt>,
+}
+
+/// One finished game of a match
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct GameOutcome {
+    /// Whether the first agent played X
+    pub first_agent_was_x: bool,
+    pub result: WonByPlayer,
+    /// The game was lost by this error rather than played out
+    pub forfeit: Option<AgentError>,
+}
+
+/// Plays up to `config.games` games between `agents`, the first agent takes X in
+/// even games. A game an agent forfeits (gave up or illegal move) counts as lost.
+/// `after_game` sees every game and the score so far
+pub fn run_tournament(
+    agents: [&mut dyn Agent; 2],
+    config: &TournamentConfig,
+    mut after_game: impl FnMut(&GameOutcome, &Score),
+) -> (Score, Option<SprtStatus>) {
+    let [first, second] = agents;
+    let mut rng = Rng::new(config.seed);
+    let mut score = Score::default();
+    let mut opening = Game::new();
+    for index in 0..config.games {
+        if let Some(sprt) = config.sprt {
+            let status = sprt.status(&score);
+            if status != SprtStatus::Continue {
+                return (score, Some(status));
+            }
+        }
+
+        let first_agent_was_x = index % 2 == 0;
+        if first_agent_was_x {
+            opening = random_opening(config.random_plies, &mut rng);
+        }
+        let mut game = opening;
+        let played = if first_agent_was_x {
+            play_game(
+                &mut game,
+                [&mut *first, &mut *second],
+                &config.limits,
+                |_, _| (),
+            )
+        } else {
+            play_game(
+                &mut game,
+                [&mut *second, &mut *first],
+                &config.limits,
+                |_, _| (),
+            )
+        };
+        let (result, forfeit) = match played {
+            Ok(result) => (result, None),
+            //agent 0 played X, so its forfeit is a win for O
+            Err(error) => {
+                let (AgentError::GaveUp { agent_index }
+                | AgentError::IllegalMove { agent_index, .. }) = error;
+                let winner = if agent_index == 0 {
+                    WonByPlayer::O
+                } else {
+                    WonByPlayer::X
+                };
+                (winner, Some(error))
+            }
+        };
+        match (result, first_agent_was_x) {
+            (WonByPlayer::X, true) | (WonByPlayer::O, false) => score.wins += 1,
+            (WonByPlayer::X, false) | (WonByPlayer::O, true) => score.losses += 1,
+            _ => score.draws += 1,
+        }
+        let outcome = GameOutcome {
+            first_agent_was_x,
+            result,
+            forfeit,
+        };
+        after_game(&outcome, &score);
+    }
+    (score, config.sprt.map(|sprt| sprt.status(&score)))
+}
+
+/// Game after `plies` uniformly random moves, stopping short of finishing it
+fn random_opening(plies: u32, rng: &mut Rng) -> Game {
+    let mut game = Game::new();
+    for _ in 0..plies {
+        let moves = game.legal_moves();
+        let mv = moves.get(rng.below(moves.len() as u32) as usize).unwrap();
+        game.make_move(mv).unwrap();
+        if game.is_finished() {
+            game.unmake_move();
+            break;
+        }
+    }
+    game
+}
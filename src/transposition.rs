@@ -0,0 +1,138 @@
+use crate::moves::Move;
+
+/// How a stored score relates to the real value of the position
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Bound {
+    Exact,
+    /// The search failed high, the real value is at least the score
+    Lower,
+    /// The search failed low, the real value is at most the score
+    Upper,
+}
+
+/// One search result, keyed by [`Game::hash`](crate::Game::hash)
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct TtEntry {
+    pub key: u64,
+    /// Remaining depth the score was searched to
+    pub depth: u8,
+    pub bound: Bound,
+    pub score: i32,
+    pub best_move: Option<Move>,
+}
+
+/// Probe counters of a [`TranspositionTable`]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Debug)]
+pub struct TtStats {
+    pub probes: u64,
+    pub hits: u64,
+}
+
+impl TtStats {
+    /// Share of probes that found their position, 0 before the first probe
+    pub fn hit_rate(&self) -> f64 {
+        if self.probes == 0 {
+            return 0.0;
+        }
+        self.hits as f64 / self.probes as f64
+    }
+}
+
+/// Fixed size hash table of search results with one entry per slot, a deeper
+/// entry is only replaced by another position searched at least as deep unless it
+/// was stored before the current search started
+#[derive(Clone, Debug)]
+pub struct TranspositionTable {
+    //each entry with the generation it was stored in
+    entries: Vec<Option<(TtEntry, u8)>>,
+    generation: u8,
+    stats: TtStats,
+}
+
+impl TranspositionTable {
+    /// About 6 MB
+    pub const DEFAULT_ENTRIES: usize = 1 << 18;
+
+    /// Table with `entries` slots rounded down to a power of two, at least one
+    pub fn new(entries: usize) -> Self {
+        let entries = if entries <= 1 {
+            1
+        } else {
+            1 << entries.ilog2()
+        };
+        Self {
+            entries: vec![None; entries],
+            generation: 0,
+            stats: TtStats::default(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn slot(&self, key: u64) -> usize {
+        key as usize & (self.entries.len() - 1)
+    }
+
+    /// Entry stored for `key`, if it hasn't been replaced
+    pub fn get(&self, key: u64) -> Option<TtEntry> {
+        self.entries[self.slot(key)]
+            .map(|(entry, _)| entry)
+            .filter(|entry| entry.key == key)
+    }
+
+    /// Like [`get`](Self::get) but counted in the statistics, meant for searches
+    pub fn probe(&mut self, key: u64) -> Option<TtEntry> {
+        self.stats.probes += 1;
+        let entry = self.get(key);
+        if entry.is_some() {
+            self.stats.hits += 1;
+        }
+        entry
+    }
+
+    /// Stores `entry` unless its slot holds a different position searched deeper
+    /// during the current search
+    pub fn store(&mut self, entry: TtEntry) {
+        let slot = self.slot(entry.key);
+        let replace = match self.entries[slot] {
+            Some((old, generation)) => {
+                old.key == entry.key || generation != self.generation || entry.depth >= old.depth
+            }
+            None => true,
+        };
+        if replace {
+            self.entries[slot] = Some((entry, self.generation));
+        }
+    }
+
+    /// Marks every stored entry as left over from an earlier search, so deep results
+    /// of positions that are long gone give way to the new search's
+    pub fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// Number of slots in use
+    pub fn len(&self) -> usize {
+        self.entries.iter().filter(|entry| entry.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Empties the table and resets the statistics
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+        self.stats = TtStats::default();
+    }
+
+    pub const fn stats(&self) -> TtStats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = TtStats::default();
+    }
+}
@@ -1,7 +1,13 @@
 use std::fmt::{Display, Debug};
+use std::hash::{Hash, Hasher};
 
-#[derive(PartialEq)]
-enum Mark {
+use crate::moves::{LegalMoves, Move};
+use crate::rng::Rng;
+use crate::zobrist;
+
+/// Contents of a single tile
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Mark {
     Empty = 0,
     X = 1,
     O = 2,
@@ -18,34 +24,77 @@ enum Mark {
     }
 } */
 
+/// Reason a move was rejected by [`Game::make_move`] or couldn't be built by [`Move::new`]
+/// or [`Move::from_row_col`], carries the attempted move
+///
+/// Board and tile numbers in messages are 1-based like the CLI prompts
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum BoardError {
-    MoveAtNotSentBoard,
-    MoveAtAlreadyFilledTile,
-    MoveAtAlreadyFinishedBoard,
+    MoveAtNotSentBoard { attempted: Move, sent_board_index: u8 },
+    MoveAtAlreadyFilledTile { attempted: Move },
+    MoveAtAlreadyFinishedBoard { attempted: Move },
+    MoveAfterGameFinished { result: WonByPlayer },
+    /// Board or tile index isn't in 0-8
+    IndexOutOfRange { small_board_pos: u8, tile_pos: u8 },
+    /// Row or column on the 9x9 grid isn't in 0-8
+    RowColOutOfRange { row: u8, col: u8 },
 }
 
-impl Debug for BoardError{
+impl Display for BoardError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::MoveAtNotSentBoard => write!(f, "MoveAtNotSentBoard"),
-            Self::MoveAtAlreadyFilledTile => write!(f, "MoveAtAlreadyFilledTile"),
-            Self::MoveAtAlreadyFinishedBoard => write!(f, "MoveAtAlreadyFinishedBoard"),
+        match *self {
+            Self::MoveAtNotSentBoard { attempted, sent_board_index } => write!(
+                f,
+                "can not make a move at board #{} as you were sent to board #{}",
+                attempted.small_board_pos() + 1,
+                sent_board_index + 1
+            ),
+            Self::MoveAtAlreadyFilledTile { attempted } => write!(
+                f,
+                "tile #{} of board #{} was already taken",
+                attempted.tile_pos() + 1,
+                attempted.small_board_pos() + 1
+            ),
+            Self::MoveAtAlreadyFinishedBoard { attempted } => write!(
+                f,
+                "can not make a move at board #{} as it has been completed",
+                attempted.small_board_pos() + 1
+            ),
+            Self::MoveAfterGameFinished { result } => match result {
+                WonByPlayer::X => write!(f, "the game is already over, it was won by X"),
+                WonByPlayer::O => write!(f, "the game is already over, it was won by O"),
+                _ => write!(f, "the game is already over, it was tied"),
+            },
+            Self::IndexOutOfRange { small_board_pos, tile_pos } => write!(
+                f,
+                "move ({}, {}) is out of range, board and tile indices go from 0 to 8",
+                small_board_pos, tile_pos
+            ),
+            Self::RowColOutOfRange { row, col } => write!(
+                f,
+                "square (row {}, column {}) is out of range, rows and columns go from 0 to 8",
+                row, col
+            ),
         }
     }
 }
 
-#[derive(PartialEq, Clone, Copy)]
+impl std::error::Error for BoardError {}
+
+/// Side to move, X always starts
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum Player {
     X = 1,
     O = 2,
 }
 impl Player {
-    const fn to_mark(&self) -> Mark {
+    pub const fn opponent(self) -> Player {
         match self {
-            Player::X => Mark::X,
-            Player::O => Mark::O,
+            Player::X => Player::O,
+            Player::O => Player::X,
         }
     }
+
     pub const fn get_letter(&self) -> &str {
         match self {
             Player::X => "X",
@@ -55,7 +104,8 @@ impl Player {
 }
 
 
-#[derive(Debug)]
+/// Finish status of a small board or of the whole game
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum WonByPlayer {
     X,
     O,
@@ -86,40 +136,74 @@ impl Tile {
             Mark::O => "O",
         }
     }
-    fn from_u32(value: u32) -> Self {
-        match value {
-            0 => Self {mark: Mark::Empty},
-            1 => Self {mark: Mark::X},
-            2 => Self {mark: Mark::O},
-            _ => panic!("Unknown value: {}", value),
-        }
-    }
 }
 
-/* Empty/X/O is 00,01,10 so we need (2*9=18) 18<=32 bits for the entire small board */
-struct NineTiles{
-    tiles: u32
+/// All nine tiles set
+const FULL_MASK: u16 = 0b111_111_111;
+
+/* One 9 bit mask per player, bit i is tile i (row by row), so placing, removing and
+ * reading a tile are single bit operations and lines can be checked on the mask directly */
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct NineTiles{
+    x_tiles: u16,
+    o_tiles: u16,
 }
 
 impl NineTiles{
     const fn new() -> Self{
         Self{
-            tiles: 0
+            x_tiles: 0,
+            o_tiles: 0,
         }
     }
 
     fn put_tile(&mut self, position: u8, player: Player){
-        self.tiles+=2u32.pow(2*position as u32)*(player as u32);
+        match player {
+            Player::X => self.x_tiles |= 1 << position,
+            Player::O => self.o_tiles |= 1 << position,
+        }
+    }
+
+    fn remove_tile(&mut self, position: u8){
+        self.x_tiles &= !(1 << position);
+        self.o_tiles &= !(1 << position);
     }
 
     fn get_tile(&self, position: u8) -> Tile{
-        Tile::from_u32((self.tiles/4u32.pow(position as u32))%4)
+        Tile { mark: self.get_mark(position) }
+    }
+
+    /// Mark at `position` (0-8, row by row)
+    pub const fn get_mark(&self, position: u8) -> Mark{
+        if position > 8 {
+            Mark::Empty
+        } else if self.x_tiles & 1 << position != 0 {
+            Mark::X
+        } else if self.o_tiles & 1 << position != 0 {
+            Mark::O
+        } else {
+            Mark::Empty
+        }
+    }
+
+    /// Bitmask of `player`'s tiles, bit `i` is tile `i`
+    pub const fn player_mask(&self, player: Player) -> u16{
+        match player {
+            Player::X => self.x_tiles,
+            Player::O => self.o_tiles,
+        }
+    }
+
+    /// Bitmask of empty tiles, bit `i` is tile `i`
+    pub const fn empty_mask(&self) -> u16{
+        !(self.x_tiles | self.o_tiles) & FULL_MASK
     }
 }
 
-struct SmallBoard {
+/// One of the nine 3x3 boards
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct SmallBoard {
     tiles: NineTiles,
-    filled_tiles: u8,
     won_by: WonByPlayer,
 }
 
@@ -146,13 +230,19 @@ impl Display for SmallBoard {
                 self.tiles.get_tile(5),
                 self.tiles.get_tile(6),
                 self.tiles.get_tile(7),
-                matches!(self.won_by, WonByPlayer::HasntFinished)
+                self.tiles.get_tile(8)
             ),
         }
         
     }
 }
 
+impl Default for SmallBoard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SmallBoard {
     /*fn get_board(&self) -> String {
         format!(
@@ -162,10 +252,9 @@ impl SmallBoard {
         )
     } */
     
-    const fn new() -> Self {
+    pub(crate) const fn new() -> Self {
         Self {
             tiles: NineTiles::new(),
-            filled_tiles: 0,
             won_by: WonByPlayer::HasntFinished,
         }
     }
@@ -205,24 +294,36 @@ impl SmallBoard {
         }
 
     }
-    fn place_tile_and_record_finish(&mut self, position: u8, player: Player) -> Result<&WonByPlayer, BoardError> {
-        if self.tiles.get_tile(position).mark != Mark::Empty {
-            return Err(BoardError::MoveAtAlreadyFilledTile); // Move at already filled tile
+    /// Places `player`'s tile at `position` (0-8) and records whether that finished the board,
+    /// `None` if `position` is out of range or already taken
+    pub(crate) fn place_tile_and_record_finish(&mut self, position: u8, player: Player) -> Option<&WonByPlayer> {
+        if position > 8 || self.tiles.empty_mask() & 1 << position == 0 {
+            return None;
         }
 
-        //xoring for presumably better speed
-        //(cant unset a bit as we check above for bit not being set)
         self.tiles.put_tile(position, player);
 
-        self.filled_tiles+=1;
+        //only the player who just moved can have completed a line
+        if has_line(self.tiles.player_mask(player)) {
+            self.won_by = match player {
+                Player::X => WonByPlayer::X,
+                Player::O => WonByPlayer::O,
+            };
+        } else if self.check_draw() {
+            self.won_by = WonByPlayer::Tie;
+        }
+        Some(&self.won_by)
+    }
 
-        self.tiles.get_tile(position).mark = player.to_mark();
-        self.check_finish_and_record();
-        Ok(&self.won_by)
+    /// Reverses [`Self::place_tile_and_record_finish`], moves are only ever made
+    /// on unfinished boards so the board is unfinished again afterwards
+    fn remove_tile_and_reset_finish(&mut self, position: u8){
+        self.tiles.remove_tile(position);
+        self.won_by = WonByPlayer::HasntFinished;
     }
 
     fn check_draw(&self) -> bool {
-        self.filled_tiles == 9
+        self.tiles.empty_mask() == 0
     }
 
     const fn small_horizontal_seperator() -> &'static str{
@@ -234,41 +335,20 @@ impl SmallBoard {
     }
 
     fn check_win(&self) -> WonByPlayer {
-        // since these values are being accessed more than once it would
-        // probably be a better idea to cache them or use a different structure
-        // and then apply 2 bitmasks to check if either X or O has won.
-
-        // on that now, should probably get git to work by now
-
-        if self.tiles.tiles & 0b00_00_01_00_00_01_00_00_01 == 0b00_00_01_00_00_01_00_00_01 // vertical
-            || self.tiles.tiles & 0b00_01_00_00_01_00_00_01_00 == 0b00_01_00_00_01_00_00_01_00
-            || self.tiles.tiles & 0b01_00_00_01_00_00_01_00_00 == 0b01_00_00_01_00_00_01_00_00
-            || self.tiles.tiles & 0b00_00_00_00_00_00_01_01_01 == 0b00_00_00_00_00_00_01_01_01 //horizontal
-            || self.tiles.tiles & 0b00_00_00_01_01_01_00_00_00 == 0b00_00_00_01_01_01_00_00_00
-            || self.tiles.tiles & 0b01_01_01_00_00_00_00_00_00 == 0b01_01_01_00_00_00_00_00_00
-            || self.tiles.tiles & 0b01_00_00_00_01_00_00_00_01 == 0b01_00_00_00_01_00_00_00_01 //diagonal
-            || self.tiles.tiles & 0b00_00_01_00_01_00_01_00_00 == 0b00_00_01_00_01_00_01_00_00
-        {
+        if has_line(self.tiles.x_tiles) {
             return WonByPlayer::X;
-        } else if self.tiles.tiles & 0b00_00_10_00_00_10_00_00_10 == 0b00_00_10_00_00_10_00_00_10 // vertical
-        || self.tiles.tiles & 0b00_10_00_00_10_00_00_10_00 == 0b00_10_00_00_10_00_00_10_00
-        || self.tiles.tiles & 0b10_00_00_10_00_00_10_00_00 == 0b10_00_00_10_00_00_10_00_00
-        || self.tiles.tiles & 0b00_00_00_00_00_00_10_10_10 == 0b00_00_00_00_00_00_10_10_10 //horizontal
-        || self.tiles.tiles & 0b00_00_00_10_10_10_00_00_00 == 0b00_00_00_10_10_10_00_00_00
-        || self.tiles.tiles & 0b10_10_10_00_00_00_00_00_00 == 0b10_10_10_00_00_00_00_00_00
-        || self.tiles.tiles & 0b10_00_00_00_10_00_00_00_10 == 0b10_00_00_00_10_00_00_00_10 //diagonal
-        || self.tiles.tiles & 0b00_00_10_00_10_00_10_00_00 == 0b00_00_10_00_10_00_10_00_00
-        {
+        } else if has_line(self.tiles.o_tiles) {
             return WonByPlayer::O;
         }
         WonByPlayer::HasntFinished
     }
 
+    /// A line completed with the last free tile is a win, not a tie
     fn get_current_finish_status(&self) -> WonByPlayer {
-        if self.check_draw() {
-            return WonByPlayer::Tie;
+        match self.check_win() {
+            WonByPlayer::HasntFinished if self.check_draw() => WonByPlayer::Tie,
+            status => status,
         }
-        self.check_win()
     }
 
     fn check_finish_and_record(&mut self) -> bool {
@@ -291,7 +371,7 @@ impl SmallBoard {
 
 
 
-    fn is_finished(&self) -> bool{
+    pub fn is_finished(&self) -> bool{
         match self.won_by{
             WonByPlayer::X => true,
             WonByPlayer::O => true,
@@ -299,14 +379,67 @@ impl SmallBoard {
             WonByPlayer::HasntFinished => false,
         }
     }
+
+    pub const fn won_by(&self) -> &WonByPlayer{
+        &self.won_by
+    }
+
+    pub const fn filled_tiles(&self) -> u8{
+        (self.tiles.x_tiles | self.tiles.o_tiles).count_ones() as u8
+    }
+
+    pub const fn tiles(&self) -> &NineTiles{
+        &self.tiles
+    }
+
+    /// Mark at `position` (0-8, row by row)
+    pub fn get_mark(&self, position: u8) -> Mark{
+        self.tiles.get_mark(position)
+    }
+}
+
+/// The eight lines of a 3x3 board as bitmasks, bit `i` is square `i`
+pub(crate) const LINES: [u16; 8] = [
+    0b001001001, 0b010010010, 0b100100100, // vertical
+    0b000000111, 0b000111000, 0b111000000, // horizontal
+    0b100010001, 0b001010100, // diagonal
+];
+
+/// `WIN_TABLE[bits]` is whether the 9 bit occupancy `bits` contains a full line,
+/// shared by small boards (tiles) and the big board (won boards)
+const WIN_TABLE: [bool; 512] = {
+    let mut table = [false; 512];
+    let mut bits = 0;
+    while bits < 512 {
+        let mut line = 0;
+        while line < LINES.len() {
+            if bits as u16 & LINES[line] == LINES[line] {
+                table[bits] = true;
+            }
+            line += 1;
+        }
+        bits += 1;
+    }
+    table
+};
+
+/// Whether the 3x3 bitmask `bits` (bit `i` is square `i`, row by row) contains a full line,
+/// bits above the ninth are ignored
+pub(crate) const fn has_line(bits: u16) -> bool {
+    WIN_TABLE[(bits & FULL_MASK) as usize]
 }
 
-struct BigBoard {
+/// The 3x3 grid of small boards plus the board the next player was sent to
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct BigBoard {
     boards: [SmallBoard; 9],
     x_bits: u16,
     o_bits: u16,
-    filled_boards: u8,
+    //won by either player or tied
+    finished_bits: u16,
     last_sent_board_index: Option<u8>,
+    hash: u64,
+    won_by: WonByPlayer,
 }
 
 impl Display for BigBoard {
@@ -333,78 +466,189 @@ impl Display for BigBoard {
 impl BigBoard {
     const fn new() -> Self {
         const INIT: SmallBoard = SmallBoard::new();
-        Self { boards: [INIT; 9], last_sent_board_index: None, x_bits: 0, o_bits: 0, filled_boards: 0 }
+        Self { boards: [INIT; 9], last_sent_board_index: None, x_bits: 0, o_bits: 0, finished_bits: 0, hash: 0, won_by: WonByPlayer::HasntFinished }
     }
-    fn current_move_can_be_put_anywhere(&self)->bool{
-        self.last_sent_board_index.is_none() || self.boards[usize::from(self.last_sent_board_index.unwrap())].is_finished()
-        
+    pub const fn current_move_can_be_put_anywhere(&self)->bool{
+        match self.last_sent_board_index {
+            Some(index) => self.finished_bits & 1 << index != 0,
+            None => true,
+        }
     }
-    fn make_move(&mut self, small_board_pos: u8, tile_pos: u8, player: Player) -> Result<WonByPlayer, BoardError> {
-        if !self.current_move_can_be_put_anywhere() && small_board_pos!=self.last_sent_board_index.unwrap(){
-            return Err(BoardError::MoveAtNotSentBoard) //illegal board
+
+    /// Small board at `index` (0-8, row by row), panics if `index` is out of range
+    pub fn get_small_board(&self, index: u8) -> &SmallBoard{
+        &self.boards[usize::from(index)]
+    }
+
+    pub const fn last_sent_board_index(&self) -> Option<u8>{
+        self.last_sent_board_index
+    }
+
+    /// Bitmask of small boards won by `player`, bit `i` is board `i`
+    pub const fn won_boards(&self, player: Player) -> u16{
+        match player {
+            Player::X => self.x_bits,
+            Player::O => self.o_bits,
+        }
+    }
+
+    /// Bitmask of small boards that are won or tied, bit `i` is board `i`
+    pub const fn finished_boards(&self) -> u16{
+        self.finished_bits
+    }
+
+    pub const fn filled_boards(&self) -> u8{
+        self.finished_bits.count_ones() as u8
+    }
+
+    /// Zobrist hash of the position, updated incrementally on every move
+    pub const fn hash(&self) -> u64{
+        self.hash
+    }
+
+    /// Zobrist hash recomputed from the tiles, matches [`Self::hash`]
+    pub fn compute_hash(&self) -> u64{
+        let mut hash = zobrist::sent_board_key(self.last_sent_board_index);
+        let mut filled_tiles = 0;
+        for (small_board_pos, board) in (0u8..).zip(self.boards.iter()){
+            filled_tiles += board.filled_tiles();
+            for tile_pos in 0..9{
+                match board.get_mark(tile_pos){
+                    Mark::X => hash ^= zobrist::tile_key(Player::X, small_board_pos, tile_pos),
+                    Mark::O => hash ^= zobrist::tile_key(Player::O, small_board_pos, tile_pos),
+                    Mark::Empty => (),
+                }
+            }
+        }
+        //X always starts so O is to move after an odd number of tiles
+        if filled_tiles % 2 == 1{
+            hash ^= zobrist::SIDE_KEY;
         }
+        hash
+    }
+
+    /// Finish status of the whole game
+    pub const fn won_by(&self) -> &WonByPlayer{
+        &self.won_by
+    }
 
-        if self.current_move_can_be_put_anywhere() && self.boards[usize::from(small_board_pos)].is_finished(){
-            return Err(BoardError::MoveAtAlreadyFinishedBoard)
+    pub const fn is_finished(&self) -> bool{
+        !matches!(self.won_by, WonByPlayer::HasntFinished)
+    }
+
+    /// Bitmask of tiles the next move can be placed on, see [`LegalMoves`] for the layout,
+    /// empty once the game is over
+    pub fn legal_move_mask(&self) -> u128{
+        let mut mask = 0u128;
+        if self.is_finished(){
+            return mask;
+        }
+        if self.current_move_can_be_put_anywhere(){
+            let mut unfinished = !self.finished_bits & FULL_MASK;
+            while unfinished != 0 {
+                let index = unfinished.trailing_zeros() as usize;
+                mask |= u128::from(self.boards[index].tiles.empty_mask()) << (9*index);
+                unfinished &= unfinished - 1;
+            }
+        } else {
+            let index = self.last_sent_board_index.unwrap();
+            mask = u128::from(self.boards[usize::from(index)].tiles.empty_mask()) << (9*index);
+        }
+        mask
+    }
+    fn make_move(&mut self, attempted: Move, player: Player) -> Result<WonByPlayer, BoardError> {
+        if self.is_finished(){
+            return Err(BoardError::MoveAfterGameFinished { result: self.won_by })
         }
 
-        //linter dies so type annotation needed
-        let small_board_finished: &WonByPlayer=self.boards[usize::from(small_board_pos)].place_tile_and_record_finish(tile_pos, player)?; //return BoardError:MoveAtAlreadyFilledTile if errors out
-        
+        let small_board_pos = attempted.small_board_pos();
+        let tile_pos = attempted.tile_pos();
 
-        match small_board_finished{
-            WonByPlayer::X => {self.x_bits ^= 1 << small_board_pos; self.filled_boards+=1},
-            WonByPlayer::O => {self.o_bits ^= 1 << small_board_pos; self.filled_boards+=1},
-            WonByPlayer::Tie =>self.filled_boards+=1,
-            WonByPlayer::HasntFinished => (),
+        if !self.current_move_can_be_put_anywhere() && small_board_pos!=self.last_sent_board_index.unwrap(){
+            return Err(BoardError::MoveAtNotSentBoard {
+                attempted,
+                sent_board_index: self.last_sent_board_index.unwrap(),
+            }) //illegal board
         }
 
+        if self.current_move_can_be_put_anywhere() && self.finished_bits & 1 << small_board_pos != 0{
+            return Err(BoardError::MoveAtAlreadyFinishedBoard { attempted })
+        }
+
+        let small_board_finished = *self.boards[usize::from(small_board_pos)]
+            .place_tile_and_record_finish(tile_pos, player)
+            .ok_or(BoardError::MoveAtAlreadyFilledTile { attempted })?;
+        self.record_small_board_finish(small_board_pos, small_board_finished);
+
         
+        self.hash ^= zobrist::tile_key(player, small_board_pos, tile_pos)
+            ^ zobrist::SIDE_KEY
+            ^ zobrist::sent_board_key(self.last_sent_board_index)
+            ^ zobrist::sent_board_key(Some(tile_pos));
         self.last_sent_board_index = Some(tile_pos);
         
-        Ok(self.get_current_finish_status())
+        //the big board can only change when a small board does
+        if small_board_finished != WonByPlayer::HasntFinished{
+            self.won_by = self.get_current_finish_status();
+        }
+        Ok(self.won_by)
+    }
+
+    /// Takes back `last_move`, `previous_sent_board_index` is what `last_sent_board_index`
+    /// was before that move
+    fn unmake_move(&mut self, last_move: Move, previous_sent_board_index: Option<u8>){
+        let small_board_pos = last_move.small_board_pos();
+        let tile_pos = last_move.tile_pos();
+        let board = &mut self.boards[usize::from(small_board_pos)];
+        let player = match board.get_mark(tile_pos){
+            Mark::X => Player::X,
+            Mark::O => Player::O,
+            Mark::Empty => unreachable!("unmaking a move on an empty tile"),
+        };
+        self.hash ^= zobrist::tile_key(player, small_board_pos, tile_pos)
+            ^ zobrist::SIDE_KEY
+            ^ zobrist::sent_board_key(self.last_sent_board_index)
+            ^ zobrist::sent_board_key(previous_sent_board_index);
+
+        self.x_bits &= !(1 << small_board_pos);
+        self.o_bits &= !(1 << small_board_pos);
+        self.finished_bits &= !(1 << small_board_pos);
+        board.remove_tile_and_reset_finish(tile_pos);
+
+        self.last_sent_board_index = previous_sent_board_index;
+        //no moves are accepted once the game is over, so it wasn't before this one
+        self.won_by = WonByPlayer::HasntFinished;
+    }
+
+    fn record_small_board_finish(&mut self, small_board_pos: u8, won_by: WonByPlayer){
+        match won_by{
+            WonByPlayer::X => self.x_bits |= 1 << small_board_pos,
+            WonByPlayer::O => self.o_bits |= 1 << small_board_pos,
+            WonByPlayer::Tie => (),
+            WonByPlayer::HasntFinished => return,
+        }
+        self.finished_bits |= 1 << small_board_pos;
     }
 
     fn check_draw(&self) -> bool {
-        self.filled_boards == 9
+        self.finished_bits == FULL_MASK
     }
 
     fn check_win(&self) -> WonByPlayer {
-        // since these values are being accessed more than once it would
-        // probably be a better idea to cache them or use a different structure
-        // and then apply 2 bitmasks to check if either X or O has won.
-
-        // on that now, should probably get git to work by now
-
-        if self.x_bits & 0b001001001 == 0b001001001 // vertical
-            || self.x_bits & 0b010010010 == 0b010010010
-            || self.x_bits & 0b100100100 == 0b100100100
-            || self.x_bits & 0b000000111 == 0b000000111 //horizontal
-            || self.x_bits & 0b000111000 == 0b000111000
-            || self.x_bits & 0b111000000 == 0b111000000
-            || self.x_bits & 0b100010001 == 0b100010001 //diagonal
-            || self.x_bits & 0b001010100 == 0b001010100
-        {
+        if has_line(self.x_bits) {
             return WonByPlayer::X;
-        } else if self.o_bits & 0b001001001 == 0b001001001 //vertical
-            || self.o_bits & 0b010010010 == 0b010010010
-            || self.o_bits & 0b100100100 == 0b100100100
-            || self.o_bits & 0b000000111 == 0b000000111 //horizontal
-            || self.o_bits & 0b000111000 == 0b000111000
-            || self.o_bits & 0b111000000 == 0b111000000
-            || self.o_bits & 0b100010001 == 0b100010001 //diagonal
-            || self.o_bits & 0b001010100 == 0b001010100
-        {
+        } else if has_line(self.o_bits) {
             return WonByPlayer::O;
         }
         WonByPlayer::HasntFinished
     }
 
+    /// A line completed with the last free board is a win, not a tie
     fn get_current_finish_status(&self) -> WonByPlayer {
-        if self.check_draw() {
-            return WonByPlayer::Tie;
+        match self.check_win() {
+            WonByPlayer::HasntFinished if self.check_draw() => WonByPlayer::Tie,
+            status => status,
         }
-        self.check_win()
     }
 
     const fn horizontal_seperator() -> &'static str{
@@ -413,7 +657,7 @@ impl BigBoard {
     #[rustfmt::skip]
     fn get_row(&self, row: u8) -> String {
         let index_usize= usize::from(row);
-        return format!(
+        format!(
             "{}║{}║{}\n\
             {}\n\
              {}║{}║{}\n\
@@ -434,12 +678,12 @@ impl BigBoard {
             self.boards[3*index_usize].get_row(2),
             self.boards[3*index_usize+1].get_row(2),
             self.boards[3*index_usize+2].get_row(2),
-        );
+        )
 
     }
     #[rustfmt::skip]
     fn get_board(&self) -> String {
-        return format!(
+        format!(
             "{}\n\
              {}\n\
              {}\n\
@@ -452,13 +696,23 @@ impl BigBoard {
             BigBoard::horizontal_seperator(),
             self.get_row(2),
         
-        );
+        )
     }
 }
 
+/// A game of Ultimate Tic Tac Toe, the entry point of the library
+///
+/// Games compare and hash by position only (tiles, side to move and the board
+/// the next move is sent to), two move orders reaching the same position are equal
+#[derive(Clone, Copy)]
 pub struct Game {
     game: BigBoard,
     next_player: Player,
+    // fixed size so the game stays a plain value, a game can't last longer than 81 moves
+    history: [Move; 81],
+    history_len: u8,
+    //board the first move of the history was sent to, only set up positions have one
+    start_sent_board_index: Option<u8>,
 }
 
 impl Display for Game {
@@ -467,11 +721,45 @@ impl Display for Game {
     }
 }
 
+impl PartialEq for Game {
+    fn eq(&self, other: &Self) -> bool {
+        self.game == other.game && self.next_player == other.next_player
+    }
+}
+
+impl Eq for Game {}
+
+impl Hash for Game {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        //the zobrist hash already covers the side to move
+        state.write_u64(self.game.hash);
+    }
+}
+
+impl Debug for Game {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Game")
+            .field("game", &self.game)
+            .field("next_player", &self.next_player)
+            .field("history", &self.history())
+            .finish()
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Game {
     pub const fn new() -> Self {
         Game {
             game: BigBoard::new(),
             next_player: Player::X,
+            history: [Move::from_index_unchecked(0); 81],
+            history_len: 0,
+            start_sent_board_index: None,
         }
     }
 
@@ -479,15 +767,39 @@ impl Game {
         &self.next_player
     }
 
+    /// Game at an arbitrary position without history, `marks[small_board_pos][tile_pos]`.
+    /// Doesn't validate anything, see [`Game::from_position`] for that
+    pub(crate) fn from_marks(marks: &[[Mark; 9]; 9], next_player: Player, last_sent_board_index: Option<u8>) -> Self{
+        let mut game = Self::new();
+        let big_board = &mut game.game;
+        for (small_board_pos, board_marks) in (0u8..).zip(marks.iter()){
+            let board = &mut big_board.boards[usize::from(small_board_pos)];
+            for (tile_pos, mark) in (0u8..).zip(board_marks.iter()){
+                let player = match mark{
+                    Mark::X => Player::X,
+                    Mark::O => Player::O,
+                    Mark::Empty => continue,
+                };
+                board.tiles.put_tile(tile_pos, player);
+            }
+            board.check_finish_and_record();
+            let won_by = board.won_by;
+            big_board.record_small_board_finish(small_board_pos, won_by);
+        }
+        big_board.last_sent_board_index = last_sent_board_index;
+        big_board.won_by = big_board.get_current_finish_status();
+        big_board.hash = big_board.compute_hash();
+        game.next_player = next_player;
+        game.start_sent_board_index = last_sent_board_index;
+        game
+    }
+
     pub fn print_game(&self){
         println!("{}", self);
     }
 
     fn switch_next_player(&mut self){
-        self.next_player = match self.next_player {
-            Player::X => Player::O,
-            Player::O => Player::X,
-        };
+        self.next_player = self.next_player.opponent();
     } 
 
     pub fn current_move_can_be_put_anywhere(&self) -> bool{
@@ -498,10 +810,226 @@ impl Game {
         self.game.last_sent_board_index
     }
 
-    pub fn make_move(&mut self, small_board_pos: u8, tile_pos: u8) -> Result<WonByPlayer, BoardError> {
-        let successful=self.game.make_move(small_board_pos, tile_pos, self.next_player);
-        if successful.is_ok(){self.switch_next_player();}
+    pub const fn get_big_board(&self) -> &BigBoard{
+        &self.game
+    }
+
+    /// Result of the game so far, [`WonByPlayer::HasntFinished`] while moves can still be made
+    pub const fn result(&self) -> WonByPlayer{
+        self.game.won_by
+    }
+
+    pub const fn is_finished(&self) -> bool{
+        self.game.is_finished()
+    }
+
+    /// 64 bit Zobrist hash of the position (tiles, side to move and the board
+    /// the next move was sent to), meant as a transposition table key
+    pub const fn hash(&self) -> u64{
+        self.game.hash
+    }
+
+    /// Bitmask of legal moves, bit [`Move::index`] is set if `make_move` would accept that move
+    pub fn legal_move_mask(&self) -> u128{
+        self.game.legal_move_mask()
+    }
+
+    /// Iterator over legal moves, doesn't allocate
+    pub fn legal_moves(&self) -> LegalMoves{
+        LegalMoves::from_mask(self.legal_move_mask())
+    }
+
+    pub fn is_legal_move(&self, mv: Move) -> bool{
+        self.legal_move_mask() & 1 << mv.index() != 0
+    }
+
+    pub fn make_move(&mut self, mv: Move) -> Result<WonByPlayer, BoardError> {
+        let successful=self.game.make_move(mv, self.next_player);
+        if successful.is_ok(){
+            self.switch_next_player();
+            self.history[usize::from(self.history_len)] = mv;
+            self.history_len+=1;
+        }
 
         successful
     }
+
+    /// Plays uniformly random legal moves on a copy of the game until it is over and
+    /// returns the result, the game itself is left untouched
+    pub fn random_playout(&self, rng: &mut Rng) -> WonByPlayer{
+        let mut game = *self;
+        while !game.is_finished(){
+            let moves = game.legal_moves();
+            let next = moves.get(rng.below(moves.len() as u32) as usize).unwrap();
+            game.game.make_move(next, game.next_player).unwrap();
+            game.switch_next_player();
+        }
+        game.result()
+    }
+
+    /// Takes back the last move and returns it, `None` if no moves have been made
+    pub fn unmake_move(&mut self) -> Option<Move>{
+        if self.history_len == 0{
+            return None;
+        }
+        self.history_len-=1;
+        let last_move = self.history[usize::from(self.history_len)];
+        //the board a player was sent to is always the tile of the move before
+        let previous_sent_board_index = match self.history().last(){
+            Some(previous_move) => Some(previous_move.tile_pos()),
+            None => self.start_sent_board_index,
+        };
+
+        self.game.unmake_move(last_move, previous_sent_board_index);
+        self.switch_next_player();
+        Some(last_move)
+    }
+
+    /// Moves made so far, oldest first
+    pub fn history(&self) -> &[Move]{
+        &self.history[..usize::from(self.history_len)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    const LINES: [[u8; 3]; 8] = [
+        [0, 1, 2],
+        [3, 4, 5],
+        [6, 7, 8],
+        [0, 3, 6],
+        [1, 4, 7],
+        [2, 5, 8],
+        [0, 4, 8],
+        [2, 4, 6],
+    ];
+
+    /// Straightforward reading of the rules: a completed line wins, a full board without one ties
+    fn reference_status(board: &SmallBoard) -> WonByPlayer {
+        for (mark, status) in [(Mark::X, WonByPlayer::X), (Mark::O, WonByPlayer::O)] {
+            if LINES
+                .iter()
+                .any(|line| line.iter().all(|&tile| board.get_mark(tile) == mark))
+            {
+                return status;
+            }
+        }
+        if (0..9).all(|tile| board.get_mark(tile) != Mark::Empty) {
+            return WonByPlayer::Tie;
+        }
+        WonByPlayer::HasntFinished
+    }
+
+    /// Walks every configuration reachable by placing tiles of either player until
+    /// the board finishes, returns how many finished by completing a line with the ninth tile
+    fn visit(board: SmallBoard, seen: &mut HashSet<NineTiles>) -> usize {
+        let mut ninth_tile_wins = 0;
+        for tile in 0..9 {
+            if board.get_mark(tile) != Mark::Empty {
+                continue;
+            }
+            for player in [Player::X, Player::O] {
+                let mut next = board;
+                let status = *next.place_tile_and_record_finish(tile, player).unwrap();
+                if !seen.insert(*next.tiles()) {
+                    continue;
+                }
+
+                assert_eq!(status, reference_status(&next), "{:?}", next.tiles());
+                assert_eq!(*next.won_by(), status);
+                assert_eq!(next.is_finished(), status != WonByPlayer::HasntFinished);
+                if next.filled_tiles() == 9 && status != WonByPlayer::Tie {
+                    ninth_tile_wins += 1;
+                }
+
+                if !next.is_finished() {
+                    ninth_tile_wins += visit(next, seen);
+                }
+            }
+        }
+        ninth_tile_wins
+    }
+
+    #[test]
+    fn small_board_status_matches_reference_for_all_reachable_configurations() {
+        let mut seen = HashSet::new();
+        let ninth_tile_wins = visit(SmallBoard::new(), &mut seen);
+        // 3^9 tile assignments minus the empty board and the 930 that can only be
+        // reached through a finished board
+        assert_eq!(seen.len(), 18_752);
+        assert_eq!(ninth_tile_wins, 284);
+    }
+
+    #[test]
+    fn ninth_tile_completing_a_line_wins() {
+        let mut board = SmallBoard::new();
+        // _ X X
+        // X O O
+        // O X O
+        for (tile, player) in [
+            (1, Player::X),
+            (2, Player::X),
+            (3, Player::X),
+            (4, Player::O),
+            (5, Player::O),
+            (6, Player::O),
+            (7, Player::X),
+            (8, Player::O),
+        ] {
+            board.place_tile_and_record_finish(tile, player).unwrap();
+            assert!(!board.is_finished());
+        }
+        let status = *board.place_tile_and_record_finish(0, Player::X).unwrap();
+        assert_eq!(board.filled_tiles(), 9);
+        assert_eq!(status, WonByPlayer::X);
+    }
+
+    #[test]
+    fn placing_on_a_taken_or_missing_tile_is_refused() {
+        let mut board = SmallBoard::new();
+        board.place_tile_and_record_finish(4, Player::X).unwrap();
+        assert_eq!(board.place_tile_and_record_finish(4, Player::O), None);
+        assert_eq!(board.place_tile_and_record_finish(9, Player::O), None);
+        assert_eq!(board.filled_tiles(), 1);
+    }
+
+    /// The mask chain `SmallBoard::check_win` and `BigBoard::check_win` used before the lookup table
+    fn has_line_by_masks(bits: u16) -> bool {
+        bits & 0b001001001 == 0b001001001 // vertical
+            || bits & 0b010010010 == 0b010010010
+            || bits & 0b100100100 == 0b100100100
+            || bits & 0b000000111 == 0b000000111 //horizontal
+            || bits & 0b000111000 == 0b000111000
+            || bits & 0b111000000 == 0b111000000
+            || bits & 0b100010001 == 0b100010001 //diagonal
+            || bits & 0b001010100 == 0b001010100
+    }
+
+    #[test]
+    fn lookup_table_agrees_with_mask_chain_for_all_512_patterns() {
+        for bits in 0..512 {
+            assert_eq!(has_line(bits), has_line_by_masks(bits), "{bits:09b}");
+        }
+    }
+
+    #[test]
+    fn bits_above_the_ninth_are_ignored() {
+        for bits in 0..512 {
+            assert_eq!(has_line(bits | 0xFE00), has_line(bits), "{bits:09b}");
+        }
+    }
+
+    #[test]
+    fn known_line_patterns() {
+        assert_eq!((0..512).filter(|&bits| has_line(bits)).count(), 282);
+        assert!(has_line(0b111_111_111));
+        assert!(has_line(0b100_010_001));
+        assert!(!has_line(0));
+        // X's half of a drawn board: X O X / X O O / O X X
+        assert!(!has_line(0b110_001_101));
+    }
 }
@@ -0,0 +1,39 @@
+//! Zobrist keys, generated at compile time with splitmix64 so hashes are
+//! stable between builds and runs
+
+use crate::rng::splitmix64;
+use crate::uttt_game::Player;
+
+/// 2*81 tile keys, then 9 sent board keys, then the side to move key
+const KEYS: [u64; 172] = {
+    let mut keys = [0; 172];
+    let mut state = 0x7474_7474_7474_7474;
+    let mut i = 0;
+    while i < keys.len() {
+        let (next_state, key) = splitmix64(state);
+        state = next_state;
+        keys[i] = key;
+        i += 1;
+    }
+    keys
+};
+
+/// Key for `player` owning tile `tile_pos` of small board `small_board_pos`
+pub(crate) const fn tile_key(player: Player, small_board_pos: u8, tile_pos: u8) -> u64 {
+    let player_offset = match player {
+        Player::X => 0,
+        Player::O => 81,
+    };
+    KEYS[player_offset + 9 * small_board_pos as usize + tile_pos as usize]
+}
+
+/// Key for the board the next move was sent to, `None` hashes to 0
+pub(crate) const fn sent_board_key(last_sent_board_index: Option<u8>) -> u64 {
+    match last_sent_board_index {
+        Some(index) => KEYS[162 + index as usize],
+        None => 0,
+    }
+}
+
+/// Toggled on every move, set while O is to move
+pub(crate) const SIDE_KEY: u64 = KEYS[171];
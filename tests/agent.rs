@@ -0,0 +1,157 @@
+mod common;
+
+use std::io::{Cursor, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use common::mv;
+use uttt::{
+    play_game, Agent, AgentError, Game, HumanAgent, Move, RandomAgent, SearchLimits, WonByPlayer,
+};
+
+/// Plays a fixed list of moves, then gives up
+struct ScriptedAgent(Vec<Move>);
+
+impl Agent for ScriptedAgent {
+    fn choose_move(&mut self, _game: &Game, _limits: &SearchLimits) -> Option<Move> {
+        if self.0.is_empty() {
+            return None;
+        }
+        Some(self.0.remove(0))
+    }
+
+    fn name(&self) -> String {
+        "scripted".to_owned()
+    }
+}
+
+fn human(input: &str) -> HumanAgent<Cursor<Vec<u8>>, Vec<u8>> {
+    HumanAgent::new(Cursor::new(input.as_bytes().to_vec()), Vec::new())
+}
+
+#[test]
+fn random_agents_play_a_full_game() {
+    let mut game = Game::new();
+    let mut moves = 0;
+    let result = play_game(
+        &mut game,
+        [&mut RandomAgent::new(1), &mut RandomAgent::new(2)],
+        &SearchLimits::default(),
+        |_, _| moves += 1,
+    )
+    .unwrap();
+    assert_ne!(result, WonByPlayer::HasntFinished);
+    assert_eq!(result, game.result());
+    assert_eq!(moves, game.history().len());
+}
+
+#[test]
+fn human_agent_reads_board_then_tile() {
+    let game = Game::new();
+    let mut agent = human("5\n3\n");
+    assert_eq!(
+        agent.choose_move(&game, &SearchLimits::default()),
+        Some(mv(4, 2))
+    );
+}
+
+#[test]
+fn human_agent_only_asks_for_tile_when_sent() {
+    let mut game = Game::new();
+    game.make_move(mv(4, 2)).unwrap();
+    let mut agent = human("7\n");
+    assert_eq!(
+        agent.choose_move(&game, &SearchLimits::default()),
+        Some(mv(2, 6))
+    );
+}
+
+#[test]
+fn human_agent_reprompts_on_invalid_input_and_illegal_moves() {
+    let mut game = Game::new();
+    game.make_move(mv(4, 4)).unwrap();
+    // not a number, two characters, out of range, tile already taken, then a legal move
+    let mut agent = human("x\n12\n0\n5\n1\n");
+    assert_eq!(
+        agent.choose_move(&game, &SearchLimits::default()),
+        Some(mv(4, 0))
+    );
+}
+
+#[test]
+fn human_agent_gives_up_when_input_ends() {
+    let game = Game::new();
+    let mut agent = human("5\n");
+    assert_eq!(agent.choose_move(&game, &SearchLimits::default()), None);
+}
+
+#[test]
+fn humans_and_bots_can_be_mixed() {
+    let mut game = Game::new();
+    let mut x = human("5\n5\n");
+    let mut o = RandomAgent::new(3);
+    let error = play_game(
+        &mut game,
+        [&mut x, &mut o],
+        &SearchLimits::default(),
+        |_, _| {},
+    )
+    .unwrap_err();
+    // X played the centre, O replied in the centre board and X ran out of input
+    assert_eq!(error, AgentError::GaveUp { agent_index: 0 });
+    assert_eq!(game.history().len(), 2);
+    assert_eq!(game.history()[0], mv(4, 4));
+}
+
+#[test]
+fn illegal_agent_moves_abort_the_game() {
+    let mut game = Game::new();
+    let mut x = ScriptedAgent(vec![mv(4, 4)]);
+    let mut o = ScriptedAgent(vec![mv(0, 0)]);
+    let error = play_game(
+        &mut game,
+        [&mut x, &mut o],
+        &SearchLimits::default(),
+        |_, _| {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        error,
+        AgentError::IllegalMove {
+            agent_index: 1,
+            attempted: mv(0, 0)
+        }
+    );
+    assert_eq!(error.to_string(), "O played the illegal move a1");
+}
+
+#[test]
+fn two_humans_share_the_terminal() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_uttt"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    //X picks e5, O first tries the taken e5 and then d4, X runs out of input
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"5\n5\n5\n1\n")
+        .unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while child.try_wait().unwrap().is_none() {
+        if Instant::now() > deadline {
+            child.kill().unwrap();
+            panic!("the game hung waiting for input");
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    let output = child.wait_with_output().unwrap();
+    let output = String::from_utf8(output.stdout).unwrap();
+    assert!(output.contains("[O] Small board index"), "{}", output);
+    assert!(output.contains("X gave up"), "{}", output);
+    assert!(output.contains("1. e5 d4 *"), "{}", output);
+}
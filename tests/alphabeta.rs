@@ -0,0 +1,103 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use common::{mv, replay, X_WINS};
+use uttt::{
+    evaluate, is_win_score, AlphaBeta, EvalWeights, Game, SearchLimits, WonByPlayer, WIN_SCORE,
+};
+
+#[test]
+fn finds_the_winning_move() {
+    let game = replay(&X_WINS[..22]);
+    let result = AlphaBeta::new(EvalWeights::default(), 3).search(&game, &SearchLimits::default());
+    assert_eq!(result.score, WIN_SCORE - 1);
+    assert!(is_win_score(result.score));
+    assert_eq!(result.depth, 1);
+    let mut game = game;
+    assert_eq!(
+        game.make_move(result.best_move.unwrap()),
+        Ok(WonByPlayer::X)
+    );
+}
+
+#[test]
+fn takes_a_small_board_when_it_can() {
+    //X has tiles 1 and 2 of board 0 and is sent back there
+    let game = replay(&[(0, 1), (1, 0), (0, 2), (2, 0)]);
+    let result = AlphaBeta::new(EvalWeights::default(), 2).search(&game, &SearchLimits::default());
+    assert_eq!(result.best_move, Some(mv(0, 0)));
+    assert!(result.score > 0);
+}
+
+#[test]
+fn principal_variation_is_playable() {
+    let game = replay(&[(4, 4), (4, 0), (0, 4)]);
+    let result = AlphaBeta::new(EvalWeights::default(), 4).search(&game, &SearchLimits::default());
+    assert_eq!(result.depth, 4);
+    assert_eq!(result.pv.len(), 4);
+    assert_eq!(result.pv.first().copied(), result.best_move);
+    assert!(result.nodes > 0);
+    let mut game = game;
+    for &mv in &result.pv {
+        assert!(game.make_move(mv).is_ok());
+    }
+}
+
+#[test]
+fn stops_when_the_time_budget_is_used_up() {
+    let start = Instant::now();
+    let result = AlphaBeta::default().search(
+        &Game::new(),
+        &SearchLimits::move_time(Duration::from_millis(100)),
+    );
+    assert!(start.elapsed() < Duration::from_secs(2));
+    assert!(result.depth >= 1);
+    assert!(Game::new().is_legal_move(result.best_move.unwrap()));
+}
+
+#[test]
+fn finished_game_has_no_best_move() {
+    let game = replay(&X_WINS);
+    let result = AlphaBeta::default().search(&game, &SearchLimits::default());
+    assert_eq!(result.best_move, None);
+    assert!(result.pv.is_empty());
+}
+
+#[test]
+fn evaluation_counts_the_free_first_move() {
+    let weights = EvalWeights::default();
+    assert_eq!(evaluate(&Game::new(), &weights), weights.free_move);
+    //after the centre tile of the centre board X is ahead, so O (to move) is behind
+    let game = replay(&[(4, 4)]);
+    assert!(evaluate(&game, &EvalWeights::default()) < 0);
+}
+
+#[test]
+fn search_reports_transposition_hits() {
+    let game = replay(&[(4, 4), (4, 0)]);
+    let mut searcher = AlphaBeta::new(EvalWeights::default(), 5);
+    let result = searcher.search(&game, &SearchLimits::default());
+    assert!(result.tt_stats.probes > 0);
+    assert!(result.tt_stats.hits > 0);
+    assert!(result.tt_stats.hit_rate() <= 1.0);
+    assert!(!searcher.transposition_table().is_empty());
+
+    //a second search of the same position starts from what the first one stored
+    let again = searcher.search(&game, &SearchLimits::default());
+    assert!(again.nodes < result.nodes);
+    assert!(again.tt_stats.hit_rate() > result.tt_stats.hit_rate());
+}
+
+#[test]
+fn tiny_table_still_finds_the_win() {
+    let mut game = replay(&X_WINS[..22]);
+    let result = AlphaBeta::new(EvalWeights::default(), 3)
+        .with_tt_entries(1)
+        .search(&game, &SearchLimits::default());
+    assert_eq!(result.score, WIN_SCORE - 1);
+    assert_eq!(
+        game.make_move(result.best_move.unwrap()),
+        Ok(WonByPlayer::X)
+    );
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use std::io::Cursor;
+
+use common::mv;
+use uttt::{play_codingame, Agent, CodinGameError, Game, Move, SearchLimits};
+
+/// Always plays the legal move with the lowest index
+struct FirstMove;
+
+impl Agent for FirstMove {
+    fn choose_move(&mut self, game: &Game, _limits: &SearchLimits) -> Option<Move> {
+        game.legal_moves().next()
+    }
+
+    fn name(&self) -> String {
+        "first".to_owned()
+    }
+}
+
+/// One referee turn: the opponent's move (if any) and the valid actions after it
+fn turn(game: &Game, opponent_move: Option<Move>) -> String {
+    let (row, col) = opponent_move.map_or((-1, -1), |mv| (mv.row() as i8, mv.col() as i8));
+    let mut turn = format!("{} {}\n{}\n", row, col, game.legal_moves().len());
+    //the referee doesn't list the actions in our index order
+    let mut actions: Vec<Move> = game.legal_moves().collect();
+    actions.sort_by_key(|mv| (mv.row(), mv.col()));
+    for action in actions {
+        turn += &format!("{} {}\n", action.row(), action.col());
+    }
+    turn
+}
+
+fn run(input: &str) -> Result<String, CodinGameError> {
+    let mut output = Vec::new();
+    play_codingame(
+        Cursor::new(input.to_owned()),
+        &mut output,
+        &mut FirstMove,
+        &SearchLimits::default(),
+    )?;
+    Ok(String::from_utf8(output).unwrap())
+}
+
+#[test]
+fn plays_a_whole_game_as_either_side() {
+    for we_start in [true, false] {
+        //the referee's side plays the highest legal move
+        let mut game = Game::new();
+        let mut input = String::new();
+        let mut expected = String::new();
+        let mut opponent_move = None;
+        if !we_start {
+            let first = game.legal_moves().last().unwrap();
+            game.make_move(first).unwrap();
+            opponent_move = Some(first);
+        }
+        while !game.is_finished() {
+            input += &turn(&game, opponent_move);
+            let ours = game.legal_moves().next().unwrap();
+            expected += &format!("{} {}\n", ours.row(), ours.col());
+            game.make_move(ours).unwrap();
+            if game.is_finished() {
+                break;
+            }
+            let theirs = game.legal_moves().last().unwrap();
+            game.make_move(theirs).unwrap();
+            opponent_move = Some(theirs);
+        }
+        assert_eq!(run(&input).unwrap(), expected);
+    }
+}
+
+#[test]
+fn rows_and_columns_are_on_the_9x9_grid() {
+    //X took the top right tile of the centre board, so O has to play in the top right board
+    let output = run("3 5\n9\n0 6\n0 7\n0 8\n1 6\n1 7\n1 8\n2 6\n2 7\n2 8\n").unwrap();
+    assert_eq!(output, "0 6\n");
+    assert_eq!(Move::from_row_col(3, 5).unwrap(), mv(4, 2));
+}
+
+#[test]
+fn bad_input_is_reported() {
+    assert!(matches!(
+        run("-1 -1\nmany\n"),
+        Err(CodinGameError::InvalidLine { line }) if line == "many"
+    ));
+    assert!(matches!(
+        run("4\n"),
+        Err(CodinGameError::InvalidLine { .. })
+    ));
+    assert!(matches!(
+        run("-1 -1\n1\n4 4\n"),
+        Err(CodinGameError::ActionsMismatch { expected, actual })
+            if expected.len() == 81 && actual == [mv(4, 4)]
+    ));
+    //our first move a1 sends the opponent to the top left board, 8 8 is outside it
+    let mut input = turn(&Game::new(), None);
+    input += "8 8\n1\n0 0\n";
+    assert!(matches!(
+        run(&input),
+        Err(CodinGameError::IllegalOpponentMove { row: 8, col: 8, .. })
+    ));
+}
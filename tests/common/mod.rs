@@ -0,0 +1,47 @@
+//! Helpers shared by the integration tests
+#![allow(dead_code)]
+
+use uttt::{Game, Move};
+
+pub fn mv(small_board_pos: u8, tile_pos: u8) -> Move {
+    Move::new(small_board_pos, tile_pos).unwrap()
+}
+
+pub fn replay(moves: &[(u8, u8)]) -> Game {
+    let mut game = Game::new();
+    for &(board, tile) in moves {
+        game.make_move(mv(board, tile)).unwrap();
+    }
+    game
+}
+
+/// 23 moves after which X has won the diagonal of boards 0, 4 and 8
+#[rustfmt::skip]
+pub const X_WINS: [(u8, u8); 23] = [
+    (8, 8), (8, 0), (0, 4), (4, 1), (1, 1), (1, 4), (4, 5), (5, 4), (4, 3), (3, 2), (2, 1), (1, 8),
+    (8, 4), (4, 8), (8, 1), (1, 0), (0, 2), (2, 4), (4, 4), (7, 0), (0, 6), (6, 8), (8, 7),
+];
+
+/// Small LCG so the random games in tests are the same on every run
+pub struct Lcg(pub u64);
+
+impl Lcg {
+    pub fn next_below(&mut self, bound: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) as usize % bound
+    }
+
+    /// Plays a uniformly random legal move, `None` once the game is over
+    pub fn play_random_move(&mut self, game: &mut Game) -> Option<Move> {
+        let moves = game.legal_moves();
+        if moves.len() == 0 {
+            return None;
+        }
+        let next = moves.get(self.next_below(moves.len())).unwrap();
+        game.make_move(next).unwrap();
+        Some(next)
+    }
+}
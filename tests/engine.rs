@@ -0,0 +1,143 @@
+mod common;
+
+use std::io::Cursor;
+use std::thread;
+use std::time::Duration;
+
+use common::X_WINS;
+use uttt::{Engine, Game, Move};
+
+/// Runs the engine on `input` and returns its output lines
+fn run(input: &str) -> Vec<String> {
+    let output = Engine::run(Cursor::new(input.to_owned()), Vec::new()).unwrap();
+    String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(str::to_owned)
+        .collect()
+}
+
+fn best_move(lines: &[String]) -> &str {
+    lines
+        .iter()
+        .find_map(|line| line.strip_prefix("bestmove "))
+        .expect("no bestmove")
+}
+
+fn record_moves(moves: &[(u8, u8)]) -> String {
+    let moves: Vec<String> = moves
+        .iter()
+        .map(|&(board, tile)| Move::new(board, tile).unwrap().to_string())
+        .collect();
+    moves.join(" ")
+}
+
+#[test]
+fn identifies_itself() {
+    let lines = run("uti\nisready\n");
+    assert!(lines[0].starts_with("id name uttt"));
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("option name Engine")));
+    assert_eq!(lines[lines.len() - 2], "utiok");
+    assert_eq!(lines[lines.len() - 1], "readyok");
+}
+
+#[test]
+fn depth_search_reports_every_iteration() {
+    let lines = run("position startpos moves e5 e4\ngo depth 3\n");
+    let infos: Vec<&String> = lines
+        .iter()
+        .filter(|line| line.starts_with("info depth"))
+        .collect();
+    assert_eq!(infos.len(), 3);
+    for (depth, info) in infos.iter().enumerate() {
+        assert!(info.starts_with(&format!("info depth {} score cp ", depth + 1)));
+        assert!(info.contains(" nodes ") && info.contains(" tthits ") && info.contains(" pv "));
+    }
+    assert_eq!(
+        lines.last().unwrap(),
+        &format!("bestmove {}", best_move(&lines))
+    );
+
+    let mut game = Game::new();
+    for mv in ["e5", "e4", best_move(&lines)] {
+        game.make_move(mv.parse().unwrap()).unwrap();
+    }
+}
+
+#[test]
+fn finds_the_win_from_a_position_string() {
+    let game = common::replay(&X_WINS[..22]);
+    let lines = run(&format!("position pos {}\ngo depth 2\n", game.position()));
+    assert!(lines.iter().any(|line| line.contains("score win 1")));
+    let mut game = game;
+    game.make_move(best_move(&lines).parse().unwrap()).unwrap();
+    assert!(game.is_finished());
+
+    //the same position reached through the move list
+    let lines = run(&format!(
+        "position startpos moves {}\ngo depth 2\n",
+        record_moves(&X_WINS[..22])
+    ));
+    assert!(lines.iter().any(|line| line.contains("score win 1")));
+}
+
+#[test]
+fn finished_game_has_no_best_move() {
+    let lines = run(&format!(
+        "position startpos moves {}\ngo depth 2\n",
+        record_moves(&X_WINS)
+    ));
+    assert_eq!(best_move(&lines), "none");
+}
+
+#[test]
+fn mcts_engine_counts_iterations() {
+    let lines = run(
+        "setoption name Engine value mcts\nsetoption name Threads value 2\ngo iterations 500\n",
+    );
+    assert!(lines.iter().any(|line| line.contains(" nodes 500 ")));
+    let mv: Move = best_move(&lines).parse().unwrap();
+    assert!(Game::new().is_legal_move(mv));
+}
+
+#[test]
+fn every_engine_ends_under_every_limit() {
+    for engine in ["alphabeta", "mcts"] {
+        for limit in ["depth 2", "nodes 300", "iterations 300", "movetime 20"] {
+            let lines = run(&format!(
+                "setoption name Engine value {}\ngo {}\n",
+                engine, limit
+            ));
+            let mv: Move = best_move(&lines).parse().unwrap();
+            assert!(Game::new().is_legal_move(mv), "{} {}", engine, limit);
+        }
+    }
+}
+
+#[test]
+fn infinite_search_runs_until_stop() {
+    let mut engine = Engine::new(Vec::new());
+    engine.handle_line("go infinite").unwrap();
+    thread::sleep(Duration::from_millis(50));
+    engine.handle_line("stop").unwrap();
+    let output = String::from_utf8(engine.into_output()).unwrap();
+    assert!(output.lines().last().unwrap().starts_with("bestmove "));
+
+    //and at the end of input without a stop
+    let lines = run("go\n");
+    assert!(best_move(&lines).parse::<Move>().is_ok());
+}
+
+#[test]
+fn bad_commands_are_reported_and_ignored() {
+    let lines = run(
+        "frobnicate\nposition startpos moves e5 a1\nposition pos 9/9 x -\n\
+         go depth x\nsetoption name Colour value red\nsetoption name Threads value 0\n\
+         setoption name Threads value 257\nquit\nisready\n",
+    );
+    assert_eq!(lines.len(), 7);
+    assert!(lines.iter().all(|line| line.starts_with("info string ")));
+    assert!(lines[1].contains("a1"));
+}
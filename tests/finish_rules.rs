@@ -0,0 +1,29 @@
+mod common;
+
+use common::mv;
+use uttt::{Game, WonByPlayer};
+
+/// Random game whose last move finishes the ninth small board and completes a line
+#[rustfmt::skip]
+const NINTH_BOARD_WINS: [(u8, u8); 41] = [
+    (0, 4), (4, 4), (4, 1), (1, 5), (5, 8), (8, 3), (3, 8), (8, 5), (5, 4), (4, 0), (0, 6),
+    (6, 8), (8, 8), (8, 0), (0, 2), (2, 1), (1, 4), (4, 7), (7, 3), (3, 1), (1, 6), (6, 5),
+    (5, 3), (3, 2), (2, 2), (2, 7), (7, 2), (2, 5), (5, 5), (6, 2), (2, 0), (4, 6), (7, 8),
+    (8, 6), (7, 5), (4, 8), (2, 3), (3, 0), (1, 2), (2, 8), (2, 6),
+];
+
+#[test]
+fn ninth_board_completing_a_line_wins_the_game() {
+    let mut game = Game::new();
+    let (&last, moves) = NINTH_BOARD_WINS.split_last().unwrap();
+    for (board, tile) in moves.iter().copied() {
+        assert_eq!(
+            game.make_move(mv(board, tile)),
+            Ok(WonByPlayer::HasntFinished)
+        );
+    }
+    assert_eq!(game.get_big_board().filled_boards(), 8);
+    assert_eq!(game.make_move(mv(last.0, last.1)), Ok(WonByPlayer::X));
+    assert_eq!(game.get_big_board().filled_boards(), 9);
+    assert_eq!(game.result(), WonByPlayer::X);
+}
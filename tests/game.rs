@@ -0,0 +1,159 @@
+mod common;
+
+use common::{mv, X_WINS};
+use uttt::{BoardError, Game, Mark, Move, Player, WonByPlayer};
+
+#[test]
+fn new_game_starts_with_x_anywhere() {
+    let game = Game::new();
+    assert!(*game.get_next_player() == Player::X);
+    assert!(game.current_move_can_be_put_anywhere());
+    assert_eq!(game.last_sent_board_index(), None);
+}
+
+#[test]
+fn move_sends_opponent_to_board_of_tile() {
+    let mut game = Game::new();
+    let status = game.make_move(mv(4, 2)).unwrap();
+    assert!(matches!(status, WonByPlayer::HasntFinished));
+    assert!(*game.get_next_player() == Player::O);
+    assert_eq!(game.last_sent_board_index(), Some(2));
+    assert!(!game.current_move_can_be_put_anywhere());
+    assert_eq!(game.get_big_board().get_small_board(4).get_mark(2), Mark::X);
+    assert_eq!(game.get_big_board().get_small_board(4).filled_tiles(), 1);
+}
+
+#[test]
+fn move_at_not_sent_board_is_rejected() {
+    let mut game = Game::new();
+    game.make_move(mv(4, 2)).unwrap();
+    assert_eq!(
+        game.make_move(mv(3, 0)),
+        Err(BoardError::MoveAtNotSentBoard {
+            attempted: mv(3, 0),
+            sent_board_index: 2
+        })
+    );
+    // a rejected move does not pass the turn
+    assert!(*game.get_next_player() == Player::O);
+}
+
+#[test]
+fn move_at_filled_tile_is_rejected() {
+    let mut game = Game::new();
+    game.make_move(mv(4, 4)).unwrap();
+    assert_eq!(
+        game.make_move(mv(4, 4)),
+        Err(BoardError::MoveAtAlreadyFilledTile {
+            attempted: mv(4, 4)
+        })
+    );
+}
+
+/// X takes the top row of board 0, O is sent back and forth between 1 and 2
+fn play_x_wins_board_0(game: &mut Game) {
+    for (board, tile) in [(0, 1), (1, 0), (0, 2), (2, 0), (0, 0)] {
+        game.make_move(mv(board, tile)).unwrap();
+    }
+}
+
+#[test]
+fn small_board_win_is_recorded() {
+    let mut game = Game::new();
+    play_x_wins_board_0(&mut game);
+    let big_board = game.get_big_board();
+    assert!(matches!(
+        big_board.get_small_board(0).won_by(),
+        WonByPlayer::X
+    ));
+    assert!(big_board.get_small_board(0).is_finished());
+    assert_eq!(big_board.won_boards(Player::X), 0b1);
+    assert_eq!(big_board.won_boards(Player::O), 0);
+    assert_eq!(big_board.filled_boards(), 1);
+}
+
+#[test]
+fn sent_to_finished_board_can_play_anywhere() {
+    let mut game = Game::new();
+    play_x_wins_board_0(&mut game);
+    assert_eq!(game.last_sent_board_index(), Some(0));
+    assert!(game.current_move_can_be_put_anywhere());
+    assert_eq!(
+        game.make_move(mv(0, 5)),
+        Err(BoardError::MoveAtAlreadyFinishedBoard {
+            attempted: mv(0, 5)
+        })
+    );
+    assert!(game.make_move(mv(8, 8)).is_ok());
+}
+
+#[test]
+fn display_renders_the_whole_board() {
+    let mut game = Game::new();
+    game.make_move(mv(4, 4)).unwrap();
+    let rendered = game.to_string();
+    assert_eq!(rendered.lines().count(), 17);
+    assert_eq!(rendered.matches('X').count(), 1);
+}
+
+#[test]
+fn result_tracks_the_end_of_the_game() {
+    let mut game = Game::new();
+    for (i, &(board, tile)) in X_WINS.iter().enumerate() {
+        assert!(matches!(game.result(), WonByPlayer::HasntFinished));
+        assert!(!game.is_finished());
+        let status = game.make_move(mv(board, tile)).unwrap();
+        assert_eq!(status, game.result());
+        if i + 1 < X_WINS.len() {
+            assert_eq!(status, WonByPlayer::HasntFinished);
+        }
+    }
+    assert_eq!(game.result(), WonByPlayer::X);
+    assert!(game.is_finished());
+}
+
+#[test]
+fn moves_after_the_game_ended_are_rejected() {
+    let mut game = Game::new();
+    for (board, tile) in X_WINS {
+        game.make_move(mv(board, tile)).unwrap();
+    }
+    assert_eq!(game.legal_moves().len(), 0);
+    for board in 0..9 {
+        for tile in 0..9 {
+            assert_eq!(
+                game.make_move(mv(board, tile)),
+                Err(BoardError::MoveAfterGameFinished {
+                    result: WonByPlayer::X
+                })
+            );
+        }
+    }
+    assert_eq!(game.history().len(), X_WINS.len());
+
+    // taking back the winning move reopens the game
+    game.unmake_move();
+    assert_eq!(game.result(), WonByPlayer::HasntFinished);
+    assert!(game.legal_moves().len() > 0);
+}
+
+#[test]
+fn errors_describe_the_attempted_move() {
+    let mut game = Game::new();
+    game.make_move(mv(4, 2)).unwrap();
+    let error = game.make_move(mv(3, 0)).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "can not make a move at board #4 as you were sent to board #3"
+    );
+
+    game.make_move(mv(2, 4)).unwrap();
+    let error = game.make_move(mv(4, 2)).unwrap_err();
+    assert_eq!(error.to_string(), "tile #3 of board #5 was already taken");
+
+    let error: Box<dyn std::error::Error> = Box::new(Move::new(9, 0).unwrap_err());
+    assert_eq!(
+        error.to_string(),
+        "move (9, 0) is out of range, board and tile indices go from 0 to 8"
+    );
+}
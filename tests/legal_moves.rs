@@ -0,0 +1,71 @@
+mod common;
+
+use common::{mv, replay, Lcg};
+use uttt::Game;
+
+#[test]
+fn initial_position_has_81_moves() {
+    let game = Game::new();
+    assert_eq!(game.legal_moves().len(), 81);
+    assert_eq!(game.legal_move_mask(), (1u128 << 81) - 1);
+}
+
+#[test]
+fn moves_are_restricted_to_sent_board() {
+    let mut game = Game::new();
+    game.make_move(mv(4, 2)).unwrap();
+    let moves: Vec<_> = game.legal_moves().collect();
+    assert_eq!(moves, (0..9).map(|tile| mv(2, tile)).collect::<Vec<_>>());
+
+    game.make_move(mv(2, 4)).unwrap();
+    let moves: Vec<_> = game.legal_moves().collect();
+    assert_eq!(moves.len(), 8);
+    assert!(!moves.contains(&mv(4, 2)));
+    assert!(!game.is_legal_move(mv(4, 2)));
+    assert!(game.is_legal_move(mv(4, 0)));
+}
+
+#[test]
+fn finished_boards_are_excluded_when_playing_anywhere() {
+    let game = replay(&[(0, 1), (1, 0), (0, 2), (2, 0), (0, 0)]);
+    assert!(game.current_move_can_be_put_anywhere());
+    assert!(game.legal_moves().all(|next| next.small_board_pos() != 0));
+    assert_eq!(game.legal_moves().len(), 81 - 9 - 2);
+}
+
+#[test]
+fn get_matches_iteration_order() {
+    let mut game = Game::new();
+    game.make_move(mv(4, 4)).unwrap();
+    let moves = game.legal_moves();
+    for (n, expected) in moves.enumerate() {
+        assert_eq!(moves.get(n), Some(expected));
+    }
+    assert_eq!(moves.get(moves.len()), None);
+}
+
+/// Compares the legal move list against trial and error with `make_move` along
+/// a few pseudo random games
+#[test]
+fn legal_moves_agree_with_make_move() {
+    for seed in 1..=8u64 {
+        let mut rng = Lcg(seed);
+        let mut game = Game::new();
+        loop {
+            for board in 0..9 {
+                for tile in 0..9 {
+                    let mut trial = game;
+                    assert_eq!(
+                        trial.make_move(mv(board, tile)).is_ok(),
+                        game.is_legal_move(mv(board, tile)),
+                        "move ({board}, {tile}) after {:?}",
+                        game.history()
+                    );
+                }
+            }
+            if rng.play_random_move(&mut game).is_none() {
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,159 @@
+mod common;
+
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use common::replay;
+use uttt::{AlphaBeta, EvalWeights, Game, Mcts, Parallelism, SearchLimits, StopFlag};
+
+#[test]
+fn time_budget_combines_move_time_and_clock() {
+    assert_eq!(SearchLimits::default().time_budget(), None);
+    let second = Duration::from_secs(1);
+    assert_eq!(SearchLimits::move_time(second).time_budget(), Some(second));
+
+    //a twentieth of the clock plus three quarters of the increment
+    let clock = SearchLimits::clock(Duration::from_secs(20), Duration::from_millis(400));
+    assert_eq!(clock.time_budget(), Some(Duration::from_millis(1300)));
+    //but never more than half of what is left
+    let short = SearchLimits::clock(Duration::from_secs(1), Duration::from_secs(2));
+    assert_eq!(short.time_budget(), Some(Duration::from_millis(500)));
+
+    let both = SearchLimits {
+        move_time: Some(Duration::from_millis(100)),
+        ..clock
+    };
+    assert_eq!(both.time_budget(), Some(Duration::from_millis(100)));
+}
+
+#[test]
+fn alpha_beta_honours_depth_and_node_limits() {
+    let game = replay(&[(4, 4), (4, 0)]);
+    let mut searcher = AlphaBeta::new(EvalWeights::default(), 64);
+    assert_eq!(searcher.search(&game, &SearchLimits::depth(3)).depth, 3);
+
+    let result = searcher.search(&Game::new(), &SearchLimits::nodes(5_000));
+    assert!(result.nodes <= 5_001);
+    assert!(result.best_move.is_some());
+    //playouts count as nodes
+    let result = searcher.search(&Game::new(), &SearchLimits::iterations(5_000));
+    assert!(result.nodes <= 5_001);
+
+    //the searcher's own cap still applies
+    let mut shallow = AlphaBeta::new(EvalWeights::default(), 2);
+    assert_eq!(shallow.search(&game, &SearchLimits::depth(5)).depth, 2);
+}
+
+#[test]
+fn mcts_honours_iteration_limit() {
+    let mut searcher = Mcts::new(Mcts::DEFAULT_EXPLORATION, 1_000, 0);
+    let result = searcher.search(&Game::new(), &SearchLimits::iterations(300));
+    assert_eq!(result.iterations, 300);
+    let result = searcher.search(&Game::new(), &SearchLimits::iterations(5_000));
+    assert_eq!(result.iterations, 1_000);
+    //every playout adds a node
+    let result = searcher.search(&Game::new(), &SearchLimits::nodes(200));
+    assert_eq!(result.iterations, 200);
+
+    //with a depth limit the tree stops growing once a line is that deep, here when
+    //every move of the root has a child and one of them gets a second
+    let result = searcher.search(&Game::new(), &SearchLimits::depth(2));
+    assert_eq!(result.iterations, 82);
+}
+
+#[test]
+fn stopped_flag_ends_searches_right_away() {
+    let stop = StopFlag::new();
+    stop.stop();
+    let limits = SearchLimits::default().with_stop(stop.clone());
+    assert!(limits.is_stopped());
+
+    let result = Mcts::new(Mcts::DEFAULT_EXPLORATION, u64::MAX, 0).search(&Game::new(), &limits);
+    assert_eq!(result.iterations, 0);
+    //nothing was searched, but there is still a move to play
+    assert!(result.best_move.is_some());
+
+    let result = AlphaBeta::default().search(&Game::new(), &limits);
+    assert!(Game::new().is_legal_move(result.best_move.unwrap()));
+
+    stop.reset();
+    assert!(!limits.is_stopped());
+}
+
+/// Runs `search` on another thread, raises its stop flag after 50ms and checks it
+/// returned quickly with a result
+fn stop_while_searching(search: impl Fn(&SearchLimits) -> bool + Sync) {
+    let stop = StopFlag::new();
+    let limits = SearchLimits::default().with_stop(stop.clone());
+    let start = Instant::now();
+    thread::scope(|scope| {
+        let handle = scope.spawn(|| search(&limits));
+        thread::sleep(Duration::from_millis(50));
+        stop.stop();
+        assert!(handle.join().unwrap());
+    });
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn searches_can_be_stopped_from_another_thread() {
+    stop_while_searching(|limits| {
+        let result = AlphaBeta::new(EvalWeights::default(), 81)
+            .with_tt_entries(1)
+            .search(&Game::new(), limits);
+        result.best_move.is_some()
+    });
+    stop_while_searching(|limits| unlimited_mcts().search(&Game::new(), limits).iterations > 0);
+    for parallelism in [Parallelism::Root, Parallelism::Tree] {
+        stop_while_searching(|limits| {
+            let mut mcts = unlimited_mcts().with_threads(2, parallelism);
+            mcts.search(&Game::new(), limits).iterations > 0
+        });
+    }
+}
+
+fn unlimited_mcts() -> Mcts {
+    Mcts::new(Mcts::DEFAULT_EXPLORATION, u64::MAX, 0)
+}
+
+/// Runs a search with the given limits and checks its result
+type Search = fn(&SearchLimits) -> bool;
+
+#[test]
+fn every_searcher_ends_under_every_single_limit() {
+    let searches: [(&str, Search); 4] = [
+        ("alpha-beta", |limits| {
+            let mut searcher = AlphaBeta::new(EvalWeights::default(), 81);
+            searcher.search(&Game::new(), limits).best_move.is_some()
+        }),
+        ("mcts", |limits| {
+            unlimited_mcts().search(&Game::new(), limits).iterations > 0
+        }),
+        ("root parallel mcts", |limits| {
+            let mut mcts = unlimited_mcts().with_threads(2, Parallelism::Root);
+            mcts.search(&Game::new(), limits).iterations > 0
+        }),
+        ("tree parallel mcts", |limits| {
+            let mut mcts = unlimited_mcts().with_threads(2, Parallelism::Tree);
+            mcts.search(&Game::new(), limits).iterations > 0
+        }),
+    ];
+    let limits = [
+        SearchLimits::move_time(Duration::from_millis(50)),
+        SearchLimits::depth(2),
+        SearchLimits::nodes(2_000),
+        SearchLimits::iterations(2_000),
+        SearchLimits::clock(Duration::from_secs(1), Duration::ZERO),
+    ];
+    for (name, search) in searches {
+        for limits in &limits {
+            //a search that never ends is reported rather than hanging the test
+            let (sender, receiver) = mpsc::channel();
+            let thread_limits = limits.clone();
+            thread::spawn(move || sender.send(search(&thread_limits)));
+            let finished = receiver.recv_timeout(Duration::from_secs(10));
+            assert_eq!(finished, Ok(true), "{} under {:?}", name, limits);
+        }
+    }
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use common::{replay, X_WINS};
+use uttt::{Game, Mcts, Parallelism, SearchLimits, WonByPlayer};
+
+#[test]
+fn finds_the_winning_move() {
+    let mut game = replay(&X_WINS[..22]);
+    let result =
+        Mcts::new(Mcts::DEFAULT_EXPLORATION, 5_000, 1).search(&game, &SearchLimits::default());
+    assert!(result.value > 0.9);
+    assert_eq!(
+        game.make_move(result.best_move.unwrap()),
+        Ok(WonByPlayer::X)
+    );
+}
+
+#[test]
+fn visits_cover_every_legal_move() {
+    let game = replay(&[(4, 4), (4, 0)]);
+    let result =
+        Mcts::new(Mcts::DEFAULT_EXPLORATION, 2_000, 7).search(&game, &SearchLimits::default());
+    assert_eq!(result.iterations, 2_000);
+    assert_eq!(result.visits.len(), game.legal_moves().len());
+    assert!(result
+        .visits
+        .iter()
+        .zip(game.legal_moves())
+        .all(|(&(mv, _), legal)| mv == legal));
+    let total: u32 = result.visits.iter().map(|&(_, visits)| visits).sum();
+    assert_eq!(u64::from(total), result.iterations);
+    let most_visited = result.visits.iter().map(|&(_, visits)| visits).max();
+    let best_visits = result
+        .visits
+        .iter()
+        .find(|&&(mv, _)| Some(mv) == result.best_move)
+        .map(|&(_, visits)| visits);
+    assert_eq!(best_visits, most_visited);
+    let policy_sum: f64 = result.policy().iter().map(|&(_, p)| p).sum();
+    assert!((policy_sum - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn same_seed_gives_the_same_search() {
+    let search = |seed| Mcts::new(0.7, 1_000, seed).search(&Game::new(), &SearchLimits::default());
+    assert_eq!(search(3), search(3));
+}
+
+#[test]
+fn stops_when_the_time_budget_is_used_up() {
+    let start = Instant::now();
+    let result = Mcts::new(Mcts::DEFAULT_EXPLORATION, u64::MAX, 0).search(
+        &Game::new(),
+        &SearchLimits::move_time(Duration::from_millis(100)),
+    );
+    assert!(start.elapsed() < Duration::from_secs(2));
+    assert!(result.iterations > 0);
+    assert!(Game::new().is_legal_move(result.best_move.unwrap()));
+}
+
+#[test]
+fn finished_game_has_no_best_move() {
+    let result = Mcts::default().search(&replay(&X_WINS), &SearchLimits::default());
+    assert_eq!(result.best_move, None);
+    assert!(result.visits.is_empty());
+    assert_eq!(result.iterations, 0);
+}
+
+#[test]
+fn single_thread_search_is_deterministic_in_both_modes() {
+    let game = replay(&[(4, 4), (4, 0)]);
+    for parallelism in [Parallelism::Root, Parallelism::Tree] {
+        let search = |seed| {
+            Mcts::new(Mcts::DEFAULT_EXPLORATION, 2_000, seed)
+                .with_threads(1, parallelism)
+                .search(&game, &SearchLimits::default())
+        };
+        assert_eq!(search(11), search(11));
+    }
+}
+
+#[test]
+fn root_parallel_search_splits_the_iterations() {
+    let game = replay(&[(4, 4), (4, 0)]);
+    let search = || {
+        Mcts::new(Mcts::DEFAULT_EXPLORATION, 1_001, 5)
+            .with_threads(4, Parallelism::Root)
+            .search(&game, &SearchLimits::default())
+    };
+    let result = search();
+    assert_eq!(result.iterations, 1_001);
+    let total: u32 = result.visits.iter().map(|&(_, visits)| visits).sum();
+    assert_eq!(total, 1_001);
+    //every thread has a fixed seed and iteration count
+    assert_eq!(result, search());
+}
+
+#[test]
+fn tree_parallel_search_finds_the_winning_move() {
+    let mut game = replay(&X_WINS[..22]);
+    let result = Mcts::new(Mcts::DEFAULT_EXPLORATION, 5_000, 1)
+        .with_threads(4, Parallelism::Tree)
+        .search(&game, &SearchLimits::default());
+    assert_eq!(result.iterations, 5_000);
+    assert_eq!(result.visits.len(), game.legal_moves().len());
+    let total: u32 = result.visits.iter().map(|&(_, visits)| visits).sum();
+    assert_eq!(u64::from(total), result.iterations);
+    assert!(result.value > 0.9);
+    assert_eq!(
+        game.make_move(result.best_move.unwrap()),
+        Ok(WonByPlayer::X)
+    );
+}
+
+#[test]
+fn parallel_searches_stop_when_the_time_budget_is_used_up() {
+    for parallelism in [Parallelism::Root, Parallelism::Tree] {
+        let start = Instant::now();
+        let result = Mcts::new(Mcts::DEFAULT_EXPLORATION, u64::MAX, 0)
+            .with_threads(3, parallelism)
+            .search(
+                &Game::new(),
+                &SearchLimits::move_time(Duration::from_millis(100)),
+            );
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert!(result.iterations > 0);
+        assert!(Game::new().is_legal_move(result.best_move.unwrap()));
+    }
+}
@@ -0,0 +1,65 @@
+use uttt::{BoardError, Move};
+
+#[test]
+fn out_of_range_indices_are_rejected() {
+    for (board, tile) in [(9, 0), (0, 9), (255, 255)] {
+        assert_eq!(
+            Move::new(board, tile),
+            Err(BoardError::IndexOutOfRange {
+                small_board_pos: board,
+                tile_pos: tile
+            })
+        );
+    }
+    for (row, col) in [(9, 0), (0, 9), (255, 255)] {
+        let error = Move::from_row_col(row, col).unwrap_err();
+        assert_eq!(error, BoardError::RowColOutOfRange { row, col });
+        assert!(error
+            .to_string()
+            .contains(&format!("row {}, column {}", row, col)));
+    }
+    assert_eq!(Move::from_index(81), None);
+}
+
+#[test]
+fn every_index_pair_round_trips() {
+    for board in 0..9 {
+        for tile in 0..9 {
+            let mv = Move::new(board, tile).unwrap();
+            assert_eq!(mv.small_board_pos(), board);
+            assert_eq!(mv.tile_pos(), tile);
+            assert_eq!(Move::from_index(mv.index()), Some(mv));
+            assert_eq!(Move::from_row_col(mv.row(), mv.col()), Ok(mv));
+        }
+    }
+}
+
+#[test]
+fn row_col_maps_onto_the_9x9_grid() {
+    let corner = Move::from_row_col(0, 0).unwrap();
+    assert_eq!((corner.small_board_pos(), corner.tile_pos()), (0, 0));
+
+    // row 4 col 5 is the middle row of the centre board, right column
+    let mv = Move::from_row_col(4, 5).unwrap();
+    assert_eq!((mv.small_board_pos(), mv.tile_pos()), (4, 5));
+
+    // row 7 col 1 is the bottom left board, middle row and middle column
+    let mv = Move::from_row_col(7, 1).unwrap();
+    assert_eq!((mv.small_board_pos(), mv.tile_pos()), (6, 4));
+
+    let last = Move::from_row_col(8, 8).unwrap();
+    assert_eq!((last.small_board_pos(), last.tile_pos()), (8, 8));
+}
+
+#[test]
+fn moves_parse_from_grid_coordinates() {
+    assert_eq!("a1".parse(), Ok(Move::from_row_col(0, 0).unwrap()));
+    assert_eq!("e5".parse(), Ok(Move::new(4, 4).unwrap()));
+    assert_eq!("i9".parse(), Ok(Move::new(8, 8).unwrap()));
+    assert_eq!(" b8 ".parse(), Ok(Move::from_row_col(7, 1).unwrap()));
+
+    for invalid in ["", "e", "e0", "j5", "E5", "e10", "5e"] {
+        let error = invalid.parse::<Move>().unwrap_err();
+        assert!(error.to_string().contains(invalid), "{error}");
+    }
+}
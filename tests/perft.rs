@@ -0,0 +1,46 @@
+mod common;
+
+use common::{replay, Lcg};
+use uttt::Game;
+
+/// Leaf counts of the legal move tree from the initial position
+const INITIAL_PERFT: [u64; 7] = [1, 81, 720, 6336, 55080, 473256, 4020960];
+
+#[test]
+fn perft_from_initial_position() {
+    let game = Game::new();
+    for (depth, &expected) in INITIAL_PERFT.iter().enumerate() {
+        assert_eq!(game.perft(depth as u32), expected, "depth {depth}");
+    }
+}
+
+#[test]
+fn perft_leaves_the_game_untouched() {
+    let game = replay(&[(4, 4), (4, 0)]);
+    game.perft(3);
+    assert_eq!(game, replay(&[(4, 4), (4, 0)]));
+    assert_eq!(game.history().len(), 2);
+}
+
+/// perft(n) is the sum of perft(n - 1) over the legal moves, checked on positions from
+/// random games where boards are finished and players get sent anywhere
+#[test]
+fn perft_is_consistent_along_random_games() {
+    for seed in 1..=8 {
+        let mut rng = Lcg(seed);
+        let mut game = Game::new();
+        while rng.play_random_move(&mut game).is_some() {
+            let by_moves: u64 = game
+                .legal_moves()
+                .map(|mv| {
+                    let mut child = game;
+                    child.make_move(mv).unwrap();
+                    child.perft(2)
+                })
+                .sum();
+            assert_eq!(game.perft(3), by_moves, "{}", game.position());
+        }
+        assert_eq!(game.perft(1), 0);
+        assert_eq!(game.perft(0), 1);
+    }
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use common::replay;
+use uttt::{Game, Rng, WonByPlayer};
+
+#[test]
+fn rng_is_reproducible_from_seed() {
+    let mut a = Rng::new(42);
+    let mut b = Rng::new(42);
+    let mut c = Rng::new(43);
+    let from_a: Vec<u64> = (0..16).map(|_| a.next_u64()).collect();
+    let from_b: Vec<u64> = (0..16).map(|_| b.next_u64()).collect();
+    let from_c: Vec<u64> = (0..16).map(|_| c.next_u64()).collect();
+    assert_eq!(from_a, from_b);
+    assert_ne!(from_a, from_c);
+}
+
+#[test]
+fn rng_below_stays_in_range_and_covers_it() {
+    let mut rng = Rng::new(7);
+    let mut seen = [0u32; 81];
+    for _ in 0..81_000 {
+        seen[rng.below(81) as usize] += 1;
+    }
+    // every bucket should be close to 1000
+    assert!(
+        seen.iter().all(|&count| (800..1200).contains(&count)),
+        "{seen:?}"
+    );
+
+    for _ in 0..1000 {
+        let x = rng.next_f64();
+        assert!((0.0..1.0).contains(&x));
+    }
+}
+
+#[test]
+fn playout_finishes_and_leaves_game_untouched() {
+    let game = replay(&[(4, 4), (4, 0)]);
+    let mut rng = Rng::new(1);
+    for _ in 0..100 {
+        let result = game.random_playout(&mut rng);
+        assert_ne!(result, WonByPlayer::HasntFinished);
+    }
+    assert_eq!(game, replay(&[(4, 4), (4, 0)]));
+    assert_eq!(game.history().len(), 2);
+}
+
+#[test]
+fn playouts_are_reproducible_from_seed() {
+    let game = Game::new();
+    let results = |seed| {
+        let mut rng = Rng::new(seed);
+        (0..200)
+            .map(|_| game.random_playout(&mut rng))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(results(5), results(5));
+    assert_ne!(results(5), results(6));
+}
+
+#[test]
+fn playout_of_finished_game_returns_its_result() {
+    let mut game = Game::new();
+    let mut rng = Rng::new(3);
+    while !game.is_finished() {
+        let moves = game.legal_moves();
+        let next = moves.get(rng.below(moves.len() as u32) as usize).unwrap();
+        game.make_move(next).unwrap();
+    }
+    assert_eq!(game.random_playout(&mut rng), game.result());
+}
+
+#[test]
+fn random_playouts_reach_every_result() {
+    let game = Game::new();
+    let mut rng = Rng::new(11);
+    let mut counts = [0; 3];
+    for _ in 0..3000 {
+        match game.random_playout(&mut rng) {
+            WonByPlayer::X => counts[0] += 1,
+            WonByPlayer::O => counts[1] += 1,
+            WonByPlayer::Tie => counts[2] += 1,
+            WonByPlayer::HasntFinished => unreachable!(),
+        }
+    }
+    // X's first move advantage shows up even in random play
+    assert!(counts.iter().all(|&count| count > 300), "{counts:?}");
+    assert!(counts[0] > counts[1], "{counts:?}");
+}
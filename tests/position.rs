@@ -0,0 +1,119 @@
+mod common;
+
+use common::{mv, replay, Lcg};
+use uttt::{Game, Player, PositionError, WonByPlayer};
+
+#[test]
+fn initial_and_first_move_positions() {
+    assert_eq!(Game::new().position(), "9/9/9/9/9/9/9/9/9 x -");
+    assert_eq!(replay(&[(4, 4)]).position(), "9/9/9/9/4x4/9/9/9/9 o 4");
+    assert_eq!(
+        replay(&[(4, 4), (4, 0), (0, 8)]).position(),
+        "9/9/2x6/3o5/4x4/9/9/9/9 o 8"
+    );
+}
+
+#[test]
+fn positions_round_trip_through_random_games() {
+    for seed in 1..=32 {
+        let mut rng = Lcg(seed);
+        let mut game = Game::new();
+        loop {
+            let position = game.position();
+            let restored = Game::from_position(&position).unwrap();
+            assert_eq!(restored, game, "{position}");
+            assert_eq!(restored.hash(), game.hash());
+            assert_eq!(restored.result(), game.result());
+            assert_eq!(restored.legal_move_mask(), game.legal_move_mask());
+            assert_eq!(restored.get_big_board(), game.get_big_board());
+            assert_eq!(restored.position(), position);
+            assert!(restored.history().is_empty());
+
+            if rng.play_random_move(&mut game).is_none() {
+                break;
+            }
+        }
+    }
+}
+
+#[test]
+fn restored_games_can_continue() {
+    let mut game = Game::from_position("9/9/9/9/4x4/9/9/9/9 o 4").unwrap();
+    assert!(game.make_move(mv(0, 0)).is_err());
+    game.make_move(mv(4, 0)).unwrap();
+    assert_eq!(game.unmake_move(), Some(mv(4, 0)));
+    assert_eq!(game.unmake_move(), None);
+}
+
+#[test]
+fn malformed_positions_are_rejected() {
+    let cases = [
+        ("9/9/9/9/9/9/9/9/9 x", PositionError::WrongFieldCount),
+        ("9/9/9/9/9/9/9/9 x -", PositionError::InvalidTiles),
+        ("9/9/9/9/9/9/9/9/8 x -", PositionError::InvalidTiles),
+        ("9/9/9/9/9/9/9/9/x9 x -", PositionError::InvalidTiles),
+        ("9/9/9/9/9/9/9/9/9z x -", PositionError::InvalidTiles),
+        ("9/9/9/9/9/9/9/9/9 y -", PositionError::InvalidSideToMove),
+        ("9/9/9/9/9/9/9/9/9 x 9", PositionError::InvalidSentBoard),
+    ];
+    for (position, error) in cases {
+        assert_eq!(Game::from_position(position), Err(error), "{position}");
+    }
+}
+
+#[test]
+fn inconsistent_positions_are_rejected() {
+    assert_eq!(
+        Game::from_position("9/9/9/9/4x4/9/9/9/9 x 4"),
+        Err(PositionError::TileCountMismatch {
+            x_tiles: 1,
+            o_tiles: 0,
+            next_player: Player::X
+        })
+    );
+    assert_eq!(
+        Game::from_position("9/9/9/9/4o4/9/9/9/9 o 4"),
+        Err(PositionError::TileCountMismatch {
+            x_tiles: 0,
+            o_tiles: 1,
+            next_player: Player::O
+        })
+    );
+    assert_eq!(
+        Game::from_position("9/9/9/9/4x4/9/9/9/9 o -"),
+        Err(PositionError::MissingSentBoard)
+    );
+    // X's only tile is the centre of board 4, so nothing could have sent O to board 0
+    assert_eq!(
+        Game::from_position("9/9/9/9/4x4/9/9/9/9 o 0"),
+        Err(PositionError::UnreachableSentBoard {
+            sent_board_index: 0
+        })
+    );
+    // X and O both own a full row of board 0
+    assert_eq!(
+        Game::from_position("xxx6/ooo6/x8/9/9/9/9/9/9 o 0"),
+        Err(PositionError::BothPlayersWon {
+            small_board_pos: Some(0)
+        })
+    );
+    // nine X tiles on board 0, it was won long before the last of them
+    assert_eq!(
+        Game::from_position("xxxooo3/xxxooo3/xxxoo4/9/9/9/9/9/9 o 0"),
+        Err(PositionError::ImpossibleWin {
+            small_board_pos: Some(0)
+        })
+    );
+    // X owns the top and the bottom row of boards, the game ended after the first
+    assert_eq!(
+        Game::from_position("xxxxxxxxx/1o2o2o1/2o2o2o/oo4oo1/2o5o/9/xxxxxxxxx/1o2o2o1/2o2o2o x 4"),
+        Err(PositionError::ImpossibleWin {
+            small_board_pos: None
+        })
+    );
+    let one_line = Game::from_position(
+        "xxxxxxxxx/1o2o2o1/2o2o2o/oo1x2oo1/2o5o/9/xxxxxxxx1/1o2o2o1/2o2o2o x 4",
+    )
+    .unwrap();
+    assert_eq!(one_line.result(), WonByPlayer::X);
+}
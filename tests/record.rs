@@ -0,0 +1,82 @@
+mod common;
+
+use common::{mv, replay, Lcg};
+use uttt::{BoardError, Game, Move, RecordError, WonByPlayer};
+
+#[test]
+fn move_notation_round_trips() {
+    for index in 0..81 {
+        let mv = Move::from_index(index).unwrap();
+        assert_eq!(mv.to_string().parse::<Move>(), Ok(mv));
+    }
+    assert_eq!(mv(4, 4).to_string(), "e5");
+    assert_eq!(mv(0, 0).to_string(), "a1");
+    assert_eq!(mv(2, 5).to_string(), "i2");
+    assert_eq!(mv(6, 4).to_string(), "b8");
+}
+
+#[test]
+fn record_lists_numbered_moves_and_result() {
+    assert_eq!(Game::new().record(), "*");
+    let game = replay(&[(4, 4), (4, 3), (3, 4)]);
+    assert_eq!(game.record(), "1. e5 d5 2. b5 *");
+}
+
+#[test]
+fn finished_game_record_round_trips() {
+    for seed in 1..=16 {
+        let mut rng = Lcg(seed);
+        let mut game = Game::new();
+        while rng.play_random_move(&mut game).is_some() {}
+
+        let record = game.record();
+        let replayed = Game::from_record(&record).unwrap();
+        assert_eq!(replayed, game);
+        assert_eq!(replayed.history(), game.history());
+        assert_eq!(replayed.record(), record);
+        assert!(!record.ends_with('*'));
+    }
+}
+
+#[test]
+fn move_numbers_and_result_are_optional() {
+    let numbered = Game::from_record("1. e5 d5 2. b5 *").unwrap();
+    let bare = Game::from_record("e5 d5\nb5").unwrap();
+    assert_eq!(numbered.history(), bare.history());
+    assert_eq!(bare.history(), &[mv(4, 4), mv(4, 3), mv(3, 4)]);
+}
+
+#[test]
+fn invalid_records_are_rejected() {
+    assert!(matches!(
+        Game::from_record("1. e5 z9"),
+        Err(RecordError::InvalidMove { ply: 1, .. })
+    ));
+    assert_eq!(
+        Game::from_record("1. e5 a1"),
+        Err(RecordError::IllegalMove {
+            ply: 1,
+            error: BoardError::MoveAtNotSentBoard {
+                attempted: mv(0, 0),
+                sent_board_index: 4
+            }
+        })
+    );
+    assert_eq!(
+        Game::from_record("1. e5 d5 1-0"),
+        Err(RecordError::ResultMismatch {
+            recorded: WonByPlayer::X,
+            actual: WonByPlayer::HasntFinished
+        })
+    );
+    assert!(matches!(
+        Game::from_record("1. e5 * d5"),
+        Err(RecordError::TokenAfterResult { .. })
+    ));
+
+    let error = Game::from_record("1. e5 a1").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "move 2: can not make a move at board #1 as you were sent to board #5"
+    );
+}
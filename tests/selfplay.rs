@@ -0,0 +1,175 @@
+mod common;
+
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use common::replay;
+use uttt::{
+    encode_state, generate_shards, play_self_play_game, shard_file_name, Agent, AlphaBeta, Mcts,
+    RandomAgent, Rng, Sample, SearchLimits, SelfPlayConfig, STATE_SIZE,
+};
+
+/// Fresh directory under the system temp dir, unique per test
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("uttt-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn read_samples(bytes: &[u8]) -> Vec<Sample> {
+    let mut input = Cursor::new(bytes);
+    let mut samples = Vec::new();
+    while let Some(sample) = Sample::read_from(&mut input).unwrap() {
+        samples.push(sample);
+    }
+    samples
+}
+
+fn mcts_config(shards: u32, threads: usize) -> SelfPlayConfig {
+    SelfPlayConfig {
+        shards,
+        games_per_shard: 2,
+        limits: SearchLimits::iterations(50),
+        sample_plies: 6,
+        seed: 11,
+        threads,
+    }
+}
+
+fn mcts(seed: u64) -> Box<dyn Agent> {
+    Box::new(Mcts::new(Mcts::DEFAULT_EXPLORATION, u64::MAX, seed))
+}
+
+#[test]
+fn state_is_from_the_side_to_move() {
+    let start = encode_state(&replay(&[]));
+    assert_eq!(start, [0; STATE_SIZE]);
+
+    //X wins board 3, then it is X's turn again
+    let game = replay(&[
+        (0, 0),
+        (0, 3),
+        (3, 1),
+        (1, 3),
+        (3, 2),
+        (2, 3),
+        (3, 0),
+        (0, 1),
+        (1, 0),
+        (0, 2),
+    ]);
+    let state = encode_state(&game);
+    assert_eq!(state[90], 0);
+    assert_eq!(state[0], 1);
+    assert_eq!(state[3], 2);
+    assert_eq!(state[81], 0);
+    assert_eq!(state[84], 1);
+
+    let mut o_to_move = game;
+    o_to_move
+        .make_move(o_to_move.legal_moves().get(0).unwrap())
+        .unwrap();
+    let state = encode_state(&o_to_move);
+    assert_eq!(state[90], 1);
+    assert_eq!(state[0], 2);
+    assert_eq!(state[3], 1);
+    assert_eq!(state[84], 2);
+}
+
+#[test]
+fn samples_round_trip_through_records() {
+    let mut rng = Rng::new(3);
+    let samples = play_self_play_game(
+        &mut RandomAgent::new(1),
+        &SearchLimits::default(),
+        0,
+        &mut rng,
+    )
+    .unwrap();
+    let mut bytes = Vec::new();
+    for sample in &samples {
+        sample.write_to(&mut bytes).unwrap();
+    }
+    assert_eq!(bytes.len(), samples.len() * Sample::RECORD_SIZE);
+    assert_eq!(read_samples(&bytes), samples);
+}
+
+#[test]
+fn self_play_samples_have_consistent_targets() {
+    let mut agent = Mcts::new(Mcts::DEFAULT_EXPLORATION, u64::MAX, 5);
+    let mut rng = Rng::new(5);
+    let samples =
+        play_self_play_game(&mut agent, &SearchLimits::iterations(100), 4, &mut rng).unwrap();
+
+    assert!(samples.len() >= 17);
+    assert_eq!(samples[0].state, [0; STATE_SIZE]);
+    assert_eq!(samples[0].legal_mask, (1 << 81) - 1);
+    for (ply, sample) in samples.iter().enumerate() {
+        assert_eq!(usize::from(sample.state[90]), ply % 2);
+        let total: f32 = sample.policy.iter().sum();
+        assert!((total - 1.0).abs() < 1e-4, "{}", total);
+        for (index, &probability) in sample.policy.iter().enumerate() {
+            if sample.legal_mask & 1 << index == 0 {
+                assert_eq!(probability, 0.0);
+            }
+        }
+    }
+    //the outcome flips with the side to move unless the game was tied
+    for pair in samples.windows(2) {
+        assert_eq!(pair[0].outcome, -pair[1].outcome);
+    }
+    assert!(samples.last().unwrap().outcome >= 0);
+}
+
+#[test]
+fn shards_are_reproducible_whatever_the_thread_count() {
+    let (dir_a, dir_b) = (temp_dir("shards-a"), temp_dir("shards-b"));
+    let shards_a = generate_shards(&dir_a, &mcts_config(3, 1), mcts, |_| ()).unwrap();
+    let shards_b = generate_shards(&dir_b, &mcts_config(3, 3), mcts, |_| ()).unwrap();
+
+    assert_eq!(shards_a.len(), 3);
+    for (index, (a, b)) in (0..).zip(shards_a.iter().zip(&shards_b)) {
+        assert_eq!(a.index, index);
+        assert_eq!(a.path, dir_a.join(shard_file_name(11, index)));
+        assert_eq!(a.games, 2);
+        let bytes = fs::read(&a.path).unwrap();
+        assert_eq!(bytes.len() as u64, a.samples * Sample::RECORD_SIZE as u64);
+        assert_eq!(bytes, fs::read(&b.path).unwrap());
+    }
+    assert_ne!(
+        fs::read(&shards_a[0].path).unwrap(),
+        fs::read(&shards_a[1].path).unwrap()
+    );
+    fs::remove_dir_all(dir_a).unwrap();
+    fs::remove_dir_all(dir_b).unwrap();
+}
+
+#[test]
+fn games_of_an_agent_without_a_policy_differ() {
+    let dir = temp_dir("no-policy");
+    let config = SelfPlayConfig {
+        shards: 1,
+        games_per_shard: 3,
+        limits: SearchLimits::depth(2),
+        sample_plies: 4,
+        seed: 1,
+        threads: 1,
+    };
+    let shards =
+        generate_shards(&dir, &config, |_| Box::new(AlphaBeta::default()), |_| ()).unwrap();
+
+    //every game starts from the empty board
+    let mut games: Vec<Vec<Sample>> = Vec::new();
+    for sample in read_samples(&fs::read(&shards[0].path).unwrap()) {
+        if sample.state == [0; STATE_SIZE] {
+            games.push(Vec::new());
+        }
+        games.last_mut().unwrap().push(sample);
+    }
+    assert_eq!(games.len(), 3);
+    for (index, game) in games.iter().enumerate() {
+        assert!(games[index + 1..].iter().all(|other| other != game));
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,149 @@
+use uttt::{
+    elo_from_fraction, fraction_from_elo, run_tournament, Agent, AlphaBeta, ExternalEngine, Game,
+    Move, RandomAgent, Score, SearchLimits, Sprt, SprtStatus, TournamentConfig, WonByPlayer,
+};
+
+/// Never has a move
+struct Resigner;
+
+impl Agent for Resigner {
+    fn choose_move(&mut self, _game: &Game, _limits: &SearchLimits) -> Option<Move> {
+        None
+    }
+
+    fn name(&self) -> String {
+        "resigner".to_owned()
+    }
+}
+
+fn config(games: u32, sprt: Option<Sprt>) -> TournamentConfig {
+    TournamentConfig {
+        games,
+        limits: SearchLimits::default(),
+        random_plies: 4,
+        seed: 7,
+        sprt,
+    }
+}
+
+#[test]
+fn elo_and_fraction_are_inverse() {
+    assert_eq!(elo_from_fraction(0.5), 0.0);
+    assert!((fraction_from_elo(400.0) - 10.0 / 11.0).abs() < 1e-12);
+    for elo in [-300.0, -50.0, 0.0, 25.0, 200.0] {
+        assert!((elo_from_fraction(fraction_from_elo(elo)) - elo).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn elo_difference_of_a_score() {
+    assert_eq!(Score::default().elo_difference(), None);
+    let all_wins = Score {
+        wins: 10,
+        ..Score::default()
+    };
+    assert_eq!(all_wins.elo_difference(), None);
+
+    let score = Score {
+        wins: 60,
+        losses: 40,
+        draws: 0,
+    };
+    assert_eq!(score.games(), 100);
+    assert_eq!(score.fraction(), 0.6);
+    let (elo, margin) = score.elo_difference().unwrap();
+    assert!((elo - 70.4).abs() < 0.1, "{}", elo);
+    assert!(margin > 60.0 && margin < 80.0, "{}", margin);
+}
+
+#[test]
+fn sprt_decides_clear_results() {
+    let sprt = Sprt::new(0.0, 10.0);
+    assert_eq!(sprt.status(&Score::default()), SprtStatus::Continue);
+    let even = Score {
+        wins: 10,
+        losses: 10,
+        draws: 5,
+    };
+    assert_eq!(sprt.status(&even), SprtStatus::Continue);
+
+    let strong = Score {
+        wins: 700,
+        losses: 300,
+        draws: 0,
+    };
+    assert_eq!(sprt.status(&strong), SprtStatus::AcceptH1);
+    let weak = Score {
+        wins: 300,
+        losses: 700,
+        draws: 0,
+    };
+    assert_eq!(sprt.status(&weak), SprtStatus::AcceptH0);
+}
+
+#[test]
+fn sprt_decides_a_match_without_a_single_loss() {
+    let sprt = Sprt::new(0.0, 100.0);
+    let mut score = Score::default();
+    while sprt.status(&score) == SprtStatus::Continue {
+        score.wins += 1;
+        assert!(score.wins < 100, "never decided");
+    }
+    assert_eq!(sprt.status(&score), SprtStatus::AcceptH1);
+}
+
+#[test]
+fn colours_alternate_and_every_game_counts() {
+    let mut first = RandomAgent::new(1);
+    let mut second = RandomAgent::new(2);
+    let mut outcomes = Vec::new();
+    let (score, status) = run_tournament(
+        [&mut first, &mut second],
+        &config(10, None),
+        |outcome, _| outcomes.push(*outcome),
+    );
+
+    assert_eq!(status, None);
+    assert_eq!(score.games(), 10);
+    assert_eq!(outcomes.len(), 10);
+    for (index, outcome) in outcomes.iter().enumerate() {
+        assert_eq!(outcome.first_agent_was_x, index % 2 == 0);
+        assert_eq!(outcome.forfeit, None);
+        assert!(!matches!(outcome.result, WonByPlayer::HasntFinished));
+    }
+}
+
+#[test]
+fn forfeits_count_as_losses_and_sprt_stops_early() {
+    let mut random = RandomAgent::new(3);
+    let mut resigner = Resigner;
+    let mut outcomes = Vec::new();
+    let (score, status) = run_tournament(
+        [&mut random, &mut resigner],
+        &config(1000, Some(Sprt::new(0.0, 50.0))),
+        |outcome, _| outcomes.push(*outcome),
+    );
+
+    assert_eq!(status, Some(SprtStatus::AcceptH1));
+    assert_eq!(score.losses + score.draws, 0);
+    assert!(score.wins < 1000);
+    assert!(outcomes.iter().all(|outcome| outcome.forfeit.is_some()));
+}
+
+#[test]
+fn plays_against_an_external_engine() {
+    let mut engine = ExternalEngine::spawn(env!("CARGO_BIN_EXE_uttt"), &["engine"]).unwrap();
+    assert!(engine.name().starts_with("uttt"));
+    let mut alpha_beta = AlphaBeta::default();
+    let config = TournamentConfig {
+        limits: SearchLimits::depth(2),
+        ..config(2, None)
+    };
+    let mut outcomes = Vec::new();
+    let (score, _) = run_tournament([&mut alpha_beta, &mut engine], &config, |outcome, _| {
+        outcomes.push(*outcome)
+    });
+
+    assert_eq!(score.games(), 2);
+    assert!(outcomes.iter().all(|outcome| outcome.forfeit.is_none()));
+}
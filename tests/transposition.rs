@@ -0,0 +1,83 @@
+mod common;
+
+use common::mv;
+use uttt::{Bound, TranspositionTable, TtEntry};
+
+fn entry(key: u64, depth: u8) -> TtEntry {
+    TtEntry {
+        key,
+        depth,
+        bound: Bound::Exact,
+        score: i32::from(depth),
+        best_move: Some(mv(4, 4)),
+    }
+}
+
+#[test]
+fn capacity_is_rounded_down_to_a_power_of_two() {
+    assert_eq!(TranspositionTable::new(1000).capacity(), 512);
+    assert_eq!(TranspositionTable::new(1024).capacity(), 1024);
+    assert_eq!(TranspositionTable::new(0).capacity(), 1);
+}
+
+#[test]
+fn stored_entries_can_be_probed() {
+    let mut table = TranspositionTable::new(16);
+    assert!(table.is_empty());
+    table.store(entry(3, 2));
+    assert_eq!(table.probe(3), Some(entry(3, 2)));
+    //same slot, different position
+    assert_eq!(table.probe(3 + 16), None);
+    assert_eq!(table.len(), 1);
+
+    let stats = table.stats();
+    assert_eq!((stats.probes, stats.hits), (2, 1));
+    assert_eq!(stats.hit_rate(), 0.5);
+    //get doesn't count
+    assert!(table.get(3).is_some());
+    assert_eq!(table.stats(), stats);
+}
+
+#[test]
+fn shallower_entries_dont_replace_deeper_ones() {
+    let mut table = TranspositionTable::new(16);
+    table.store(entry(1, 5));
+    table.store(entry(1 + 16, 4));
+    assert_eq!(table.get(1), Some(entry(1, 5)));
+    assert_eq!(table.get(1 + 16), None);
+
+    table.store(entry(1 + 32, 5));
+    assert_eq!(table.get(1), None);
+    assert_eq!(table.get(1 + 32), Some(entry(1 + 32, 5)));
+
+    //the same position is always updated
+    table.store(entry(1 + 32, 1));
+    assert_eq!(table.get(1 + 32), Some(entry(1 + 32, 1)));
+}
+
+#[test]
+fn entries_of_earlier_searches_give_way() {
+    let mut table = TranspositionTable::new(16);
+    table.store(entry(2, 9));
+    table.new_search();
+    //still usable until something else needs the slot
+    assert_eq!(table.get(2), Some(entry(2, 9)));
+
+    table.store(entry(2 + 16, 1));
+    assert_eq!(table.get(2), None);
+    assert_eq!(table.get(2 + 16), Some(entry(2 + 16, 1)));
+
+    //within the same search depth wins again
+    table.store(entry(2 + 32, 0));
+    assert_eq!(table.get(2 + 16), Some(entry(2 + 16, 1)));
+}
+
+#[test]
+fn clear_empties_the_table() {
+    let mut table = TranspositionTable::new(16);
+    table.store(entry(7, 1));
+    table.probe(7);
+    table.clear();
+    assert!(table.is_empty());
+    assert_eq!(table.stats().probes, 0);
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use common::{mv, Lcg};
+use uttt::{Game, Player, WonByPlayer};
+
+/// Everything observable about a game through the public API
+fn snapshot(game: &Game) -> String {
+    let big_board = game.get_big_board();
+    let mut state = format!(
+        "{} {:?} {} {} {} {:x} {:?}\n{}",
+        game.get_next_player().get_letter(),
+        game.last_sent_board_index(),
+        big_board.won_boards(Player::X),
+        big_board.won_boards(Player::O),
+        big_board.filled_boards(),
+        game.legal_move_mask(),
+        game.history(),
+        game,
+    );
+    for index in 0..9 {
+        let board = big_board.get_small_board(index);
+        state += &format!("\n{:?} {}", board.won_by(), board.filled_tiles());
+        for position in 0..9 {
+            state += &format!(" {:?}", board.get_mark(position));
+        }
+    }
+    state
+}
+
+#[test]
+fn unmake_on_new_game_does_nothing() {
+    let mut game = Game::new();
+    let before = snapshot(&game);
+    assert_eq!(game.unmake_move(), None);
+    assert_eq!(snapshot(&game), before);
+}
+
+#[test]
+fn unmake_reverts_small_board_win() {
+    let mut game = Game::new();
+    for (board, tile) in [(0, 1), (1, 0), (0, 2), (2, 0)] {
+        game.make_move(mv(board, tile)).unwrap();
+    }
+    let before = snapshot(&game);
+    game.make_move(mv(0, 0)).unwrap();
+    assert!(matches!(
+        game.get_big_board().get_small_board(0).won_by(),
+        WonByPlayer::X
+    ));
+
+    assert_eq!(game.unmake_move(), Some(mv(0, 0)));
+    assert_eq!(snapshot(&game), before);
+    assert!(!game.get_big_board().get_small_board(0).is_finished());
+    assert!(*game.get_next_player() == Player::X);
+    assert_eq!(game.last_sent_board_index(), Some(0));
+}
+
+#[test]
+fn unmake_restores_every_position_of_random_games() {
+    for seed in 1..=32u64 {
+        let mut rng = Lcg(seed);
+        let mut game = Game::new();
+        let mut snapshots = vec![snapshot(&game)];
+        while rng.play_random_move(&mut game).is_some() {
+            snapshots.push(snapshot(&game));
+        }
+
+        let played = game.history().to_vec();
+        for expected_move in played.iter().rev() {
+            snapshots.pop();
+            assert_eq!(game.unmake_move(), Some(*expected_move));
+            assert_eq!(&snapshot(&game), snapshots.last().unwrap());
+        }
+        assert_eq!(game.unmake_move(), None);
+    }
+}
+
+#[test]
+fn unmake_restores_the_sent_board_of_a_set_up_position() {
+    let position = "9/9/9/9/4xo3/9/9/9/9 x 5";
+    let mut game = Game::from_position(position).unwrap();
+    let before = snapshot(&game);
+    let legal_before: Vec<_> = game.legal_moves().collect();
+
+    game.make_move(mv(5, 4)).unwrap();
+    assert_eq!(game.unmake_move(), Some(mv(5, 4)));
+    assert_eq!(snapshot(&game), before);
+    assert_eq!(game.last_sent_board_index(), Some(5));
+    assert_eq!(game.hash(), Game::from_position(position).unwrap().hash());
+    assert_eq!(game.legal_moves().collect::<Vec<_>>(), legal_before);
+    assert_eq!(game.unmake_move(), None);
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use common::{mv, replay};
+use uttt::{BigBoard, Game, NineTiles, Player, SmallBoard, WonByPlayer};
+
+fn hash_of<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn state_types_are_copy() {
+    fn assert_value_type<T: Copy + Eq + Hash + std::fmt::Debug>() {}
+    assert_value_type::<Game>();
+    assert_value_type::<BigBoard>();
+    assert_value_type::<SmallBoard>();
+    assert_value_type::<NineTiles>();
+    assert_value_type::<WonByPlayer>();
+    assert_value_type::<Player>();
+}
+
+#[test]
+fn transpositions_compare_and_hash_equal() {
+    let a = replay(&[(4, 0), (0, 4), (4, 1), (1, 4)]);
+    let b = replay(&[(4, 1), (1, 4), (4, 0), (0, 4)]);
+    assert_ne!(a.history(), b.history());
+    assert_eq!(a, b);
+    assert_eq!(hash_of(&a), hash_of(&b));
+
+    let mut positions = HashMap::new();
+    positions.insert(a, "a");
+    assert_eq!(positions.get(&b), Some(&"a"));
+}
+
+#[test]
+fn different_positions_are_not_equal() {
+    let a = replay(&[(4, 0), (0, 4), (4, 1), (1, 4)]);
+    let b = replay(&[(4, 0), (0, 4), (4, 2), (2, 4)]);
+    assert_ne!(a, b);
+    assert_ne!(Game::new(), replay(&[(4, 4)]));
+
+    // undoing a move gives back an equal position
+    let mut c = Game::new();
+    c.make_move(mv(4, 4)).unwrap();
+    let mut d = Game::new();
+    d.make_move(mv(4, 4)).unwrap();
+    d.make_move(mv(4, 0)).unwrap();
+    d.unmake_move();
+    assert_eq!(c, d);
+    assert_eq!(c.last_sent_board_index(), Some(4));
+}
+
+#[test]
+fn copies_are_independent() {
+    let original = replay(&[(4, 4)]);
+    let mut copy = original;
+    copy.make_move(mv(4, 0)).unwrap();
+    assert_eq!(original.history(), &[mv(4, 4)]);
+    assert_eq!(copy.history(), &[mv(4, 4), mv(4, 0)]);
+    assert_ne!(original, copy);
+
+    copy.unmake_move();
+    assert_eq!(original, copy);
+    assert_eq!(hash_of(&original), hash_of(&copy));
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use common::{replay, Lcg};
+use uttt::Game;
+
+#[test]
+fn new_game_hash_matches_from_scratch() {
+    let game = Game::new();
+    assert_eq!(game.hash(), game.get_big_board().compute_hash());
+}
+
+#[test]
+fn incremental_hash_matches_from_scratch_over_random_games() {
+    for seed in 1..=64u64 {
+        let mut rng = Lcg(seed);
+        let mut game = Game::new();
+        let mut hashes = vec![game.hash()];
+        while rng.play_random_move(&mut game).is_some() {
+            assert_eq!(game.hash(), game.get_big_board().compute_hash());
+            hashes.push(game.hash());
+        }
+
+        while game.unmake_move().is_some() {
+            hashes.pop();
+            assert_eq!(game.hash(), *hashes.last().unwrap());
+            assert_eq!(game.hash(), game.get_big_board().compute_hash());
+        }
+        assert_eq!(game.hash(), Game::new().hash());
+    }
+}
+
+#[test]
+fn transpositions_have_the_same_hash() {
+    let a = replay(&[(4, 0), (0, 4), (4, 1), (1, 4)]);
+    let b = replay(&[(4, 1), (1, 4), (4, 0), (0, 4)]);
+    assert_eq!(a.hash(), b.hash());
+}
+
+#[test]
+fn hash_covers_side_to_move_and_sent_board() {
+    let a = replay(&[(4, 4)]);
+    let b = replay(&[(4, 3)]);
+    let c = replay(&[(4, 4), (4, 3)]);
+    assert_ne!(a.hash(), b.hash());
+    assert_ne!(a.hash(), Game::new().hash());
+    assert_ne!(b.hash(), c.hash());
+}